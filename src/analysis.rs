@@ -0,0 +1,144 @@
+//! Quality report for a finished card: who partnered and opposed whom, and how often.
+#![allow(non_snake_case)]
+use crate::schedule::{PlayerId, Schedule};
+
+/// Pair and sit-out counts for every player on a card. Matrices and per-player lists are
+/// indexed from 0, so player `p` lives at index `p - 1`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub playerCount: usize,
+    pub partners: Vec<Vec<usize>>,
+    pub opponents: Vec<Vec<usize>>,
+    pub sitOuts: Vec<usize>,
+    /// Rounds spent at a three- or five-handed table rather than in a partnership.
+    pub oddTables: Vec<usize>,
+    /// Tables where the player deals the first hand.
+    pub firstDeals: Vec<usize>,
+    /// Times the player changed table number between two rounds they played back to back.
+    pub moves: Vec<usize>,
+    pub totalMoves: usize,
+    pub distinctPartners: Vec<usize>,
+    pub distinctOpponents: Vec<usize>,
+    /// Most times any one pair partnered.
+    pub maxRepeatPartner: usize,
+    /// Most times any one pair opposed each other.
+    pub maxRepeatOpponent: usize,
+    /// Pairs who never shared a team.
+    pub neverPartnered: usize,
+    /// Pairs who never sat at the same table, as partners or opponents.
+    pub neverMet: usize,
+}
+
+/// Whether `player` changed tables coming into round `roundIndex`, or `None` when they sat out
+/// this round or the one before, or it is the first round.
+pub fn moved(schedule: &Schedule, roundIndex: usize, player: PlayerId) -> Option<bool> {
+
+    let previous = schedule.rounds.get(roundIndex.checked_sub(1)?)?.teamOf(player)?;
+    let current = schedule.rounds.get(roundIndex)?.teamOf(player)?;
+
+    Some(previous.0 != current.0)
+}
+
+impl Report {
+    pub fn partnerCount(&self, a: PlayerId, b: PlayerId) -> usize {
+        self.partners[a - 1][b - 1]
+    }
+
+    pub fn opponentCount(&self, a: PlayerId, b: PlayerId) -> usize {
+        self.opponents[a - 1][b - 1]
+    }
+}
+
+pub fn analyze(schedule: &Schedule, playerCount: usize) -> Report {
+
+    let mut partners = vec![vec![0; playerCount]; playerCount];
+    let mut opponents = vec![vec![0; playerCount]; playerCount];
+    let mut sitOuts = vec![0; playerCount];
+    let mut oddTables = vec![0; playerCount];
+    let mut firstDeals = vec![0; playerCount];
+
+    for round in &schedule.rounds {
+
+        for table in &round.tables {
+
+            if let Some(dealer) = table.dealerPlayer() {
+                firstDeals[dealer - 1] += 1;
+            }
+
+            if !table.isPartnership() {
+                for player in table.players() {
+                    oddTables[player - 1] += 1;
+                }
+            }
+
+            for (teamIndex, team) in table.teams.iter().enumerate() {
+                for &player in &team.players {
+
+                    for &partner in &team.players {
+                        if partner != player {
+                            partners[player - 1][partner - 1] += 1;
+                        }
+                    }
+
+                    for (otherIndex, other) in table.teams.iter().enumerate() {
+                        if otherIndex != teamIndex {
+                            for &opponent in &other.players {
+                                opponents[player - 1][opponent - 1] += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for &player in &round.out {
+            sitOuts[player - 1] += 1;
+        }
+    }
+
+    let moves: Vec<usize> = (1..=playerCount)
+        .map(|player| (0..schedule.rounds.len()).filter(|&round| moved(schedule, round, player) == Some(true)).count())
+        .collect();
+
+    let distinct = |counts: &Vec<Vec<usize>>| -> Vec<usize> {
+        counts.iter().map(|row| row.iter().filter(|&&count| count > 0).count()).collect()
+    };
+
+    let mut maxRepeatPartner = 0;
+    let mut maxRepeatOpponent = 0;
+    let mut neverPartnered = 0;
+    let mut neverMet = 0;
+
+    for (a, (partnerRow, opponentRow)) in partners.iter().zip(&opponents).enumerate() {
+        for (&partnered, &opposed) in partnerRow.iter().zip(opponentRow).skip(a + 1) {
+
+            maxRepeatPartner = maxRepeatPartner.max(partnered);
+            maxRepeatOpponent = maxRepeatOpponent.max(opposed);
+
+            if partnered == 0 {
+                neverPartnered += 1;
+
+                if opposed == 0 {
+                    neverMet += 1;
+                }
+            }
+        }
+    }
+
+    Report {
+        playerCount,
+        distinctPartners: distinct(&partners),
+        distinctOpponents: distinct(&opponents),
+        partners,
+        opponents,
+        sitOuts,
+        oddTables,
+        firstDeals,
+        totalMoves: moves.iter().sum(),
+        moves,
+        maxRepeatPartner,
+        maxRepeatOpponent,
+        neverPartnered,
+        neverMet,
+    }
+}
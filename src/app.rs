@@ -4,11 +4,21 @@ use egui::{Widget, Vec2, Frame};
 use genpdf::{Element, Mm};
 use genpdf::style::StyledString;
 use rand::Rng;
-use rand::rngs::mock::StepRng;
-use shuffle::shuffler::Shuffler;
-use shuffle::irs::Irs;
+use crate::analysis;
+use crate::awards::{self, Award, AwardKind};
+use crate::optimize::{CostWeights, Optimizer};
+use crate::import::{self, ImportedPlayer};
+use crate::playoff::{Bracket, Entrant};
+use crate::roster::{self, KnownPlayer, Roster};
+use crate::scoring::{self, Hand, HandResult, RankingRules, Scores, TableScore};
+use crate::timer::{OvertimeRule, RoundTimer};
+use crate::schedule::{self, Constraint, ConstraintKind, Leftover, Pin, PlayerId, Round, Schedule, ScheduleMode, ScheduleParams, Table, TableFormat, Team};
+use std::cell::RefCell;
 use std::fs;
+use std::ops::Range;
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -18,15 +28,89 @@ pub struct RotatorApp {
 
     // this how you opt-out of serialization of a member
     playerCount: usize,
+    mode: ScheduleMode,
+    leftover: Leftover,
+    tableFormat: TableFormat,
     tableCount: usize,
     outCount: usize,
     gameCount: usize,
+    /// Rounds already played, which stay as they are when the card is rebuilt.
+    currentRound: usize,
+    seed: u64,
     separator: String,
     displayNames: bool,
-    cardData: Vec<(Vec<((usize, usize), (usize, usize))>, Vec<usize>)>,
+    showSeats: bool,
+    keepTables: bool,
+    cardData: Schedule,
     playerNames: Vec<String>,
+    teamNames: Vec<String>,
+    constraints: Vec<Constraint>,
+    /// Players who left after playing, kept on the roster so the played rounds still read right.
+    departed: Vec<PlayerId>,
+    pins: Vec<Pin>,
+    scores: Scores,
+    /// Points that win a game.
+    target: u32,
+    rankingRules: RankingRules,
+    /// Names given to the award categories, in the order of `AwardKind::ALL`. Blank keeps the
+    /// default name.
+    awardNames: Vec<String>,
+    bracket: Option<Bracket>,
+    /// Players taken from the top of the standings into the playoff.
+    playoffSize: usize,
+    costWeights: CostWeights,
+    optimizeSeconds: f32,
+    optimizeIterations: usize,
+    timer: RoundTimer,
+    /// Saved under its own storage key, so it survives clearing or replacing tonight's state.
+    #[serde(skip)]
+    roster: Roster,
+    #[serde(skip)]
+    newConstraint: Constraint,
+    #[serde(skip)]
+    scheduleError: Option<String>,
+    #[serde(skip)]
+    optimizer: Option<Optimizer>,
+    #[serde(skip)]
+    costHistory: Vec<[f64; 2]>,
     #[serde(skip)]
     fontSettingsOpen: bool,
+    #[serde(skip)]
+    reportOpen: bool,
+    #[serde(skip)]
+    leaderboardOpen: bool,
+    /// Round and table whose hand-by-hand score sheet is open.
+    #[serde(skip)]
+    sheetOpen: Option<(usize, usize)>,
+    #[serde(skip)]
+    awardsOpen: bool,
+    #[serde(skip)]
+    rosterOpen: bool,
+    #[serde(skip)]
+    rosterFilter: String,
+    #[serde(skip)]
+    importOpen: bool,
+    #[serde(skip)]
+    importText: String,
+    /// A player list file the browser has finished reading, picked up on the next frame.
+    #[serde(skip)]
+    importFile: Rc<RefCell<Option<String>>>,
+    #[serde(skip)]
+    importError: Option<String>,
+    #[serde(skip)]
+    checkInOpen: bool,
+    /// Who has arrived, by player or by pair in fixed pairs.
+    #[serde(skip)]
+    checkedIn: Vec<bool>,
+    #[serde(skip)]
+    timerFullScreen: bool,
+    /// Set when the round timer runs out, until the scores prompt is dismissed.
+    #[serde(skip)]
+    timeUp: bool,
+    #[serde(skip)]
+    playoffOpen: bool,
+    #[serde(skip)]
+    playoffError: Option<String>,
     font_id: egui::FontId,
     gridSpacing: Vec2,
     max_col_width: f32,
@@ -36,17 +120,61 @@ pub struct RotatorApp {
 
 impl Default for RotatorApp {
     fn default() -> Self {
+        let seed = newSeed();
+
         Self {
             // Example stuff:
             playerCount: 12,
+            mode: ScheduleMode::Rotation,
+            leftover: Leftover::SitOut,
+            tableFormat: TableFormat::FourHanded,
             tableCount: 3,
             outCount: 0,
             gameCount: 11,
+            currentRound: 0,
+            seed,
             separator: String::from("-"),
             displayNames: false,
-            cardData: shuffle(12, 3, 11, 0),
+            showSeats: true,
+            keepTables: false,
+            cardData: schedule::generate(&ScheduleParams { playerCount: 12, gameCount: 11, seed, ..Default::default() }).expect("Failed to generate a rotation card"),
             playerNames: Vec::new(),
+            teamNames: Vec::new(),
+            constraints: Vec::new(),
+            departed: Vec::new(),
+            pins: Vec::new(),
+            scores: Scores::default(),
+            target: scoring::DEFAULT_TARGET,
+            rankingRules: RankingRules::default(),
+            awardNames: Vec::new(),
+            bracket: None,
+            playoffSize: 8,
+            newConstraint: Constraint { kind: ConstraintKind::NeverPartners, a: 1, b: 2 },
+            scheduleError: None,
+            costWeights: CostWeights::default(),
+            optimizeSeconds: 5.0,
+            optimizeIterations: 500_000,
+            timer: RoundTimer::default(),
+            roster: Roster::default(),
+            optimizer: None,
+            costHistory: Vec::new(),
             fontSettingsOpen: false,
+            reportOpen: false,
+            leaderboardOpen: false,
+            sheetOpen: None,
+            awardsOpen: false,
+            rosterOpen: false,
+            rosterFilter: String::new(),
+            importOpen: false,
+            importText: String::new(),
+            importFile: Rc::default(),
+            importError: None,
+            checkInOpen: false,
+            checkedIn: Vec::new(),
+            timerFullScreen: false,
+            timeUp: false,
+            playoffOpen: false,
+            playoffError: None,
             font_id: egui::FontId::default(),
             gridSpacing: Vec2::new(2.0, 2.0),
             max_col_width: 20.0,
@@ -65,7 +193,9 @@ impl RotatorApp {
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
         if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            let mut app: Self = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            app.roster = eframe::get_value(storage, roster::STORAGE_KEY).unwrap_or_default();
+            return app;
         }
 
         Default::default()
@@ -77,19 +207,58 @@ impl eframe::App for RotatorApp {
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, eframe::APP_KEY, self);
+        eframe::set_value(storage, roster::STORAGE_KEY, &self.roster);
     }
 
     /// Called each time the UI needs repainting, which may be many times per second.
     /// Put your widgets into a `SidePanel`, `TopPanel`, `CentralPanel`, `Window` or `Area`.
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        let Self { playerCount , tableCount, outCount, gameCount, separator, displayNames, cardData, playerNames, fontSettingsOpen: settingsOpen, font_id, gridSpacing , max_col_width, col_spacer, background_color} = self;
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let Self { playerCount , mode, leftover, tableFormat, tableCount, outCount, gameCount, currentRound, seed, separator, displayNames, showSeats, keepTables, cardData, playerNames, teamNames, constraints, departed, pins, scores, target, rankingRules, awardNames, bracket, playoffSize, newConstraint, scheduleError, costWeights, optimizeSeconds, optimizeIterations, timer, roster, optimizer, costHistory, fontSettingsOpen: settingsOpen, reportOpen, leaderboardOpen, sheetOpen, awardsOpen, rosterOpen, rosterFilter, importOpen, importText, importFile, importError, checkInOpen, checkedIn, timerFullScreen, timeUp, playoffOpen, playoffError, font_id, gridSpacing , max_col_width, col_spacer, background_color} = self;
+
+        let mut optimizeDone = false;
+
+        if let Some(running) = optimizer {
+
+            // A card regenerated from the side panel replaces whatever the optimizer was working on.
+            if running.best() != cardData {
+                optimizeDone = true;
+            } else {
+                running.step(OPTIMIZE_STEPS_PER_FRAME, ctx.input().time);
+
+                if running.best() != cardData {
+                    scores.carryOver(&cardData.rounds, &running.best().rounds);
+                    *cardData = running.best().clone();
+                }
+
+                costHistory.push([running.iterations() as f64, running.bestCost()]);
+
+                optimizeDone = running.finished(ctx.input().time);
+                ctx.request_repaint();
+            }
+        }
+
+        if optimizeDone {
+            *optimizer = None;
+        }
+
+        let now = ctx.input().time;
+
+        if timer.expire(now) {
+            *timeUp = true;
+        }
+
+        if timer.running() {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+
+        // Set by any side panel change that needs a new card.
+        let mut regenerate = false;
 
         // Examples of how to create different panels and windows.
         // Pick whichever suits you.
         // Tip: a good default choice is to just keep the `CentralPanel`.
         // For inspiration and more examples, go to https://emilk.github.io/egui
 
-        #[cfg(not(target_arch = "wasm32"))] // no File->Quit on web pages!
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             // The top panel is often a good place for a menu bar:
             egui::menu::bar(ui, |ui| {
@@ -103,12 +272,47 @@ impl eframe::App for RotatorApp {
                     *settingsOpen = !*settingsOpen;
                 }
 
+                #[cfg(not(target_arch = "wasm32"))] // no file dialog on web pages!
                 if ui.button("Export").clicked() {
 
                     if let Some(path) = rfd::FileDialog::new().add_filter("PDF", &["pdf"]).save_file()  {
-                        exportPDF(path, *tableCount, *outCount, *gameCount, separator.clone(), *displayNames, cardData.clone(), playerNames.clone());
+                        let options = ExportOptions {
+                            tableCount: *tableCount,
+                            outCount: *outCount,
+                            playerCount: *playerCount,
+                            separator: separator.clone(),
+                            displayNames: *displayNames,
+                            playerNames: playerNames.clone(),
+                            teamNames: pairNames(*mode, teamNames),
+                            showSeats: *showSeats,
+                            keepTables: *keepTables,
+                        };
+                        exportPDF(path, cardData, 0..cardData.rounds.len(), &options);
                     }
                 }
+
+                ui.separator();
+
+                ui.label(egui::RichText::new(format!("⏱ {}", timer.display(now))).monospace().color(timerColor(ui, timer.warning(now))));
+
+                if timer.running() {
+                    if ui.button("⏸").on_hover_text("Pause the round timer").clicked() {
+                        timer.pause(now);
+                    }
+                } else if ui.button("▶").on_hover_text("Start the round timer").clicked() {
+                    timer.start(now);
+                }
+
+                if ui.button("⟲").on_hover_text("Reset the round timer").clicked() {
+                    timer.reset();
+                }
+
+                if ui.button("⛶").on_hover_text("Full-screen countdown").clicked() {
+                    *timerFullScreen = true;
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    frame.set_fullscreen(true);
+                }
             });
         });
 
@@ -117,99 +321,461 @@ impl eframe::App for RotatorApp {
 
                 ui.add_space(10.0);
 
-                ui.label("Number of Players");
-                if ui.add(egui::Slider::new(playerCount, 4..=50).integer()).changed() {
+                ui.horizontal_wrapped(|ui| {
+                    if ui.button("Report").clicked() {
+                        *reportOpen = !*reportOpen;
+                    }
 
-                    playerNames.shrink_to(*playerCount);
+                    if ui.button("Leaderboard").clicked() {
+                        *leaderboardOpen = !*leaderboardOpen;
+                    }
 
-                    *tableCount = *playerCount / 4;
-                    *outCount = *playerCount % 4;
+                    if ui.button("Awards").clicked() {
+                        *awardsOpen = !*awardsOpen;
+                    }
+
+                    if ui.button("Playoff").clicked() {
+                        *playoffOpen = !*playoffOpen;
+                    }
+
+                    if ui.button("Roster").clicked() {
+                        *rosterOpen = !*rosterOpen;
+                    }
+                });
+
+                ui.add_space(8.0);
 
-                    *cardData = Vec::new();
-                    *cardData = shuffle(*playerCount, *tableCount, *gameCount, *outCount);
+                ui.label("Mode");
+                let mut modeChanged = false;
+
+                egui::ComboBox::from_id_source("ScheduleMode")
+                    .selected_text(mode.label())
+                    .show_ui(ui, |ui| {
+                        for option in ScheduleMode::ALL {
+                            modeChanged |= ui.selectable_value(mode, option, option.label()).changed();
+                        }
+                    });
+
+                if modeChanged {
+
+                    *optimizer = None;
+
+                    match mode {
+                        ScheduleMode::FixedPairs => {
+                            *playerCount += *playerCount % 2;
+                            *gameCount = schedule::roundRobinLength(*playerCount / 2);
+                        }
+                        ScheduleMode::Rotation => *playerCount = (*playerCount).max(tableFormat.seats()),
+                        ScheduleMode::Progressive | ScheduleMode::Swiss => *playerCount = (*playerCount).max(4),
+                    }
+                    regenerate = true;
+                }
+
+                if *mode == ScheduleMode::FixedPairs {
+
+                    let mut pairCount = *playerCount / 2;
+
+                    ui.label("Number of Pairs");
+                    if ui.add(egui::Slider::new(&mut pairCount, 2..=25).integer()).changed() {
+
+                        *playerCount = 2 * pairCount;
+                        playerNames.shrink_to(*playerCount);
+                        regenerate = true;
+                    }
+                } else {
+
+                    // Nobody who already played can be dropped off the end of the roster.
+                    let fewest = cardData.rounds.iter().take(*currentRound)
+                        .flat_map(|round| round.players().into_iter().chain(round.out.iter().copied()))
+                        .max()
+                        .unwrap_or(0)
+                        .max(if *mode == ScheduleMode::Rotation { tableFormat.seats() } else { 4 });
+
+                    ui.label("Number of Players");
+                    if ui.add(egui::Slider::new(playerCount, fewest..=50).integer()).changed() {
+
+                        playerNames.shrink_to(*playerCount);
+                        regenerate = true;
+                    }
                 }
 
+                // Cards built from results grow a round at a time, so only the plan changes here.
+                let built = if mode.roundByRound() { cardData.rounds.len() } else { *currentRound };
+
                 ui.label("Number of Rounds");
-                if ui.add(egui::Slider::new(gameCount, 1..=50).integer()).changed() {
+                if ui.add(egui::Slider::new(gameCount, built.max(1)..=50).integer()).changed() {
 
                     playerNames.shrink_to(*playerCount);
+                    regenerate = !mode.roundByRound();
+                }
 
-                    *tableCount = *playerCount / 4;
-                    *outCount = *playerCount % 4;
+                if !mode.roundByRound() {
 
-                    *cardData = Vec::new();
-                    *cardData = shuffle(*playerCount, *tableCount, *gameCount, *outCount);
+                    ui.label("Rounds Played");
+                    ui.add(egui::Slider::new(currentRound, 0..=cardData.rounds.len()).integer());
                 }
-                
+
+                if *currentRound > 0 {
+                    ui.label(egui::RichText::new(format!("Rounds 1-{} stay as they are when players come or go", currentRound)).weak());
+                }
+
+                if *mode == ScheduleMode::Rotation {
+
+                    ui.label("Table Format");
+                    let mut formatChanged = false;
+
+                    egui::ComboBox::from_id_source("TableFormat")
+                        .selected_text(tableFormat.label())
+                        .show_ui(ui, |ui| {
+                            for option in TableFormat::ALL {
+                                formatChanged |= ui.selectable_value(tableFormat, option, option.label()).changed();
+                            }
+                        });
+
+                    if formatChanged {
+
+                        *playerCount = (*playerCount).max(tableFormat.seats());
+                        regenerate = true;
+                    }
+
+                    if *tableFormat == TableFormat::FourHanded && *playerCount % 4 != 0 {
+
+                        ui.label("Leftover Players");
+                        let mut changed = false;
+
+                        egui::ComboBox::from_id_source("Leftover")
+                            .selected_text(leftover.label())
+                            .show_ui(ui, |ui| {
+                                for option in Leftover::ALL {
+                                    changed |= ui.selectable_value(leftover, option, option.label()).changed();
+                                }
+                            });
+
+                        if changed {
+                            regenerate = true;
+                        }
+                    }
+                }
+
+                if *mode == ScheduleMode::FixedPairs {
+                    ui.label(egui::RichText::new(format!("Every pair meets once in {} rounds", schedule::roundRobinLength(*playerCount / 2))).weak());
+                } else if *mode == ScheduleMode::Progressive {
+                    ui.label(egui::RichText::new("Winners move up a table and split, losers stay").weak());
+                } else if *mode == ScheduleMode::Swiss {
+                    ui.label(egui::RichText::new("Players meet others with the same record, one bye each").weak());
+                } else if let Some(exactRounds) = schedule::exactRoundCount(&ScheduleParams { playerCount: *playerCount, leftover: *leftover, format: *tableFormat, ..Default::default() }) {
+                    ui.label(egui::RichText::new(format!("No repeat partners for up to {} rounds", exactRounds)).weak());
+                }
+
+                if !mode.roundByRound() && ui.checkbox(keepTables, "Keep players at their tables").changed() {
+                    regenerate = true;
+                }
+
+                ui.label("Seed");
+                ui.horizontal(|ui| {
+                    if ui.add(egui::DragValue::new(seed)).changed() {
+                        regenerate = true;
+                    }
+
+                    if ui.button("🎲 Reroll").clicked() {
+                        *seed = newSeed();
+                        regenerate = true;
+                    }
+                });
+
+                if mode.roundByRound() {
+
+                    ui.add_space(8.0);
+                    ui.label(format!("Round {} of {}", cardData.rounds.len(), gameCount));
+
+                    let lastRound = cardData.rounds.len().saturating_sub(1);
+                    let winners = scores.winners(lastRound, cardData.rounds.last().map_or(0, |round| round.tables.len()));
+                    let ready = winners.is_ok() && cardData.rounds.len() < *gameCount && scheduleError.is_none();
+
+                    if ui.add_enabled(ready, egui::Button::new("⏭ Next Round")).clicked() {
+
+                        let params = ScheduleParams {
+                            playerCount: *playerCount,
+                            gameCount: *gameCount,
+                            seed: *seed,
+                            mode: *mode,
+                            absent: departed.clone(),
+                            ..Default::default()
+                        };
+
+                        let next = match mode {
+                            ScheduleMode::Swiss => {
+                                let ranking: Vec<PlayerId> = scoring::standings(cardData, scores, *playerCount, rankingRules).iter().map(|standing| standing.player).collect();
+                                schedule::swiss(cardData, &params, &ranking)
+                            }
+                            _ => schedule::progress(cardData, &params, &winners.clone().unwrap_or_default()),
+                        };
+
+                        match next {
+                            Ok(card) => {
+                                *currentRound = cardData.rounds.len();
+                                *cardData = card;
+                                (*tableCount, *outCount) = (cardData.tableCount(), cardData.outCount());
+
+                                // Tables need their slips for the new round straight away.
+                                #[cfg(not(target_arch = "wasm32"))]
+                                if let Some(path) = rfd::FileDialog::new().add_filter("PDF", &["pdf"]).save_file() {
+                                    let options = ExportOptions {
+                                            tableCount: *tableCount,
+                                            outCount: *outCount,
+                                            playerCount: *playerCount,
+                                            separator: separator.clone(),
+                                            displayNames: *displayNames,
+                                            playerNames: playerNames.clone(),
+                                            teamNames: pairNames(*mode, teamNames),
+                                            showSeats: *showSeats,
+                                            keepTables: *keepTables,
+                                        };
+                                    exportPDF(path, cardData, *currentRound..cardData.rounds.len(), &options);
+                                }
+                            }
+                            Err(error) => *scheduleError = Some(error.to_string()),
+                        }
+                    }
+
+                    if let Err((table, error)) = winners {
+                        ui.label(egui::RichText::new(format!("Table {}: {}", table + 1, error)).weak());
+                    }
+                }
+
+                // Swaps would split up registered pairs, and their round-robin is already exact.
+                if *mode == ScheduleMode::Rotation {
+
+                    ui.add_space(8.0);
+                    ui.label("Optimize");
+                    ui.add(egui::Slider::new(optimizeSeconds, 1.0..=60.0).text("Seconds"));
+                    ui.add(egui::Slider::new(optimizeIterations, 1_000..=5_000_000).logarithmic(true).text("Steps"));
+
+                    if optimizer.is_some() {
+                        if ui.button("⏹ Stop").clicked() {
+                            *optimizer = None;
+                        }
+                    } else if ui.add_enabled(*currentRound < cardData.rounds.len(), egui::Button::new("⚡ Optimize")).clicked() {
+                        let present = constraints.iter().copied().filter(|constraint| !departed.contains(&constraint.a) && !departed.contains(&constraint.b)).collect();
+                        let mut running = Optimizer::new(cardData.clone(), *playerCount, departed, costWeights.clone(), present, *currentRound, pins.clone(), *keepTables, *optimizeIterations);
+                        running.setDeadline(ctx.input().time + *optimizeSeconds as f64);
+                        *optimizer = Some(running);
+                        *costHistory = Vec::new();
+                    }
+
+                    if let (Some(first), Some(last)) = (costHistory.first(), costHistory.last()) {
+                        ui.label(format!("Cost {:.1} → {:.1}", first[1], last[1]));
+
+                        egui::plot::Plot::new("OptimizerCost")
+                            .height(80.0)
+                            .show_axes([false, true])
+                            .allow_drag(false)
+                            .allow_zoom(false)
+                            .allow_scroll(false)
+                            .include_y(0.0)
+                            .show(ui, |plot_ui| {
+                                plot_ui.line(egui::plot::Line::new(egui::plot::PlotPoints::new(costHistory.clone())));
+                            });
+                    }
+                }
+
+                // Rounds built from results go wherever the results send them, so there is nothing
+                // to constrain.
+                if !mode.roundByRound() {
+
+                    ui.add_space(8.0);
+                    egui::CollapsingHeader::new(format!("Constraints ({})", constraints.len())).show(ui, |ui| {
+
+                        let mut removed = None;
+
+                        for (index, constraint) in constraints.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("✖").clicked() {
+                                    removed = Some(index);
+                                }
+
+                                let names = formatPlayersVector(vec![constraint.a, constraint.b], " & ".to_owned(), true, playerNames.clone());
+                                ui.label(format!("{}: {}", constraint.kind.label(), names));
+                            });
+                        }
+
+                        if let Some(index) = removed {
+                            constraints.remove(index);
+                            regenerate = true;
+                        }
+
+                        egui::ComboBox::from_id_source("ConstraintKind")
+                            .selected_text(newConstraint.kind.label())
+                            .show_ui(ui, |ui| {
+                                for option in ConstraintKind::ALL {
+                                    ui.selectable_value(&mut newConstraint.kind, option, option.label());
+                                }
+                            });
+
+                        ui.horizontal(|ui| {
+                            playerPicker(ui, "ConstraintA", &mut newConstraint.a, *playerCount, playerNames);
+                            playerPicker(ui, "ConstraintB", &mut newConstraint.b, *playerCount, playerNames);
+
+                            if ui.add_enabled(newConstraint.a != newConstraint.b, egui::Button::new("Add")).clicked() {
+                                constraints.push(*newConstraint);
+                                regenerate = true;
+                            }
+                        });
+                    });
+                }
+
+                if *mode == ScheduleMode::Rotation {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new("Right-click a table to pin it").weak());
+
+                        if !pins.is_empty() && ui.button(format!("Clear {} Pins", pins.len())).clicked() {
+                            pins.clear();
+                        }
+                    });
+                }
+
+                ui.add_space(8.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("📋 Import Players").clicked() {
+                        *importOpen = true;
+                    }
+
+                    if ui.add_enabled(*currentRound == 0, egui::Button::new("✅ Check In")).on_disabled_hover_text("Check-in is before the first round").clicked() {
+                        *checkInOpen = true;
+                    }
+                });
+
                 ui.add_space(8.0);
                 ui.separator();
                 ui.add_space(8.0);
 
                 egui::ScrollArea::vertical().show(ui, |ui| {
-                    
+
+                    if *mode == ScheduleMode::FixedPairs {
+
+                        for pairId in 0..*playerCount / 2 {
+
+                            if teamNames.len() <= pairId {
+                                teamNames.push("".to_owned());
+                            }
+
+                            for playerId in [2 * pairId, 2 * pairId + 1] {
+                                if playerNames.len() <= playerId {
+                                    playerNames.push("".to_owned());
+                                }
+                            }
+
+                            ui.horizontal(|ui| {
+                                ui.label(format!("Pair {}", pairId + 1));
+                                egui::TextEdit::singleline(&mut teamNames[pairId]).hint_text("Team Name").ui(ui);
+                            });
+
+                            ui.horizontal(|ui| {
+                                ui.add_space(10.0);
+                                nameField(ui, &mut playerNames[2 * pairId], format!("Player {}", 2 * pairId + 1), Some(90.0), roster);
+                                nameField(ui, &mut playerNames[2 * pairId + 1], format!("Player {}", 2 * pairId + 2), Some(90.0), roster);
+                            });
+                        }
+
+                        return;
+                    }
+
                     for playerId in 0..*playerCount {
 
                         if playerNames.len() <= playerId {
                             playerNames.push("".to_owned());
                         }
         
+                        let left = departed.contains(&(playerId + 1));
+                        // Once rounds are played, players are renumbered only if they haven't played yet.
+                        let playedCount = (*currentRound).min(cardData.rounds.len());
+                        let played = &mut cardData.rounds[..playedCount];
+
                         ui.horizontal(|ui| {
                             ui.label(format!("Player {}", playerId + 1));
-                            egui::TextEdit::singleline(&mut playerNames[playerId])
-                                .hint_text("Name").ui(ui)
+                            nameField(ui, &mut playerNames[playerId], String::from("Name"), None, roster)
                                 .context_menu(|ui| {
+                                    if left {
+                                        if ui.button("Rejoin").clicked() {
+                                            departed.retain(|&player| player != playerId + 1);
+                                            regenerate = true;
+                                        }
+                                        return;
+                                    }
                                     if ui.button("Insert Before").clicked() {
                                         *playerCount += 1;
                                         playerNames.insert(playerId, "".to_owned());
+                                        shiftConstraints(constraints, playerId + 1, 1);
+                                        shiftPlayers(played, departed, pins, scores, bracket, playerId + 1, 1);
                         
-                                        *tableCount = *playerCount / 4;
-                                        *outCount = *playerCount % 4;
+                                        (*tableCount, *outCount) = tableShape(*playerCount, *mode, *leftover, *tableFormat);
                         
-                                        if *outCount == 0 {
-                                            *gameCount = *playerCount - 1;
-                                        } else {
-                                            *gameCount = *playerCount;
+                                        if *currentRound == 0 {
+                                            if *outCount == 0 {
+                                                *gameCount = *playerCount - 1;
+                                            } else {
+                                                *gameCount = *playerCount;
+                                            }
                                         }
                         
-                                        *cardData = shuffle(*playerCount, *tableCount, *gameCount, *outCount);
+                                        regenerate = true;
                                     }
                                     if ui.button("Insert After").clicked() {
                                         *playerCount += 1;
                                         playerNames.insert(playerId + 1, "".to_owned());
+                                        shiftConstraints(constraints, playerId + 2, 1);
+                                        shiftPlayers(played, departed, pins, scores, bracket, playerId + 2, 1);
 
-                                        *tableCount = *playerCount / 4;
-                                        *outCount = *playerCount % 4;
+                                        (*tableCount, *outCount) = tableShape(*playerCount, *mode, *leftover, *tableFormat);
                         
-                                        if *outCount == 0 {
-                                            *gameCount = *playerCount - 1;
-                                        } else {
-                                            *gameCount = *playerCount;
+                                        if *currentRound == 0 {
+                                            if *outCount == 0 {
+                                                *gameCount = *playerCount - 1;
+                                            } else {
+                                                *gameCount = *playerCount;
+                                            }
                                         }
                         
-                                        *cardData = shuffle(*playerCount, *tableCount, *gameCount, *outCount);
+                                        regenerate = true;
                                     }
                                     ui.add(egui::Separator::default());
                                     if ui.button("Delete").clicked() {
 
-                                        if *playerCount == 4 {
+                                        if *playerCount - departed.len() == tableFormat.seats() {
+                                            return;
+                                        }
+
+                                        // Someone who already played leaves the rest of the card
+                                        // but stays on the roster for the rounds they were in.
+                                        if played.iter().any(|round| round.players().contains(&(playerId + 1)) || round.out.contains(&(playerId + 1))) {
+                                            departed.push(playerId + 1);
+                                            regenerate = true;
                                             return;
                                         }
 
                                         *playerCount -= 1;
                                         playerNames.remove(playerId);
+                                        shiftConstraints(constraints, playerId + 1, -1);
+                                        shiftPlayers(played, departed, pins, scores, bracket, playerId + 1, -1);
 
-                                        *tableCount = *playerCount / 4;
-                                        *outCount = *playerCount % 4;
+                                        (*tableCount, *outCount) = tableShape(*playerCount, *mode, *leftover, *tableFormat);
                         
-                                        if *outCount == 0 {
-                                            *gameCount = *playerCount - 1;
-                                        } else {
-                                            *gameCount = *playerCount;
+                                        if *currentRound == 0 {
+                                            if *outCount == 0 {
+                                                *gameCount = *playerCount - 1;
+                                            } else {
+                                                *gameCount = *playerCount;
+                                            }
                                         }
                         
-                                        *cardData = shuffle(*playerCount, *tableCount, *gameCount, *outCount);
+                                        regenerate = true;
                                     }
                                 });
+
+                            if left {
+                                ui.label(egui::RichText::new("left").weak());
+                            }
                         });
                     }
                 });
@@ -223,6 +789,269 @@ impl eframe::App for RotatorApp {
             });
         });
 
+        if let Some(text) = importFile.borrow_mut().take() {
+            *importText = text;
+        }
+
+        let mut imported: Option<Vec<ImportedPlayer>> = None;
+
+        egui::Window::new("📋 Import Players")
+            .open(importOpen)
+            .show(ctx, |ui| {
+
+                ui.label(format!("One player per line, with the columns {}. Only the name is needed.", import::COLUMNS.join(", ")));
+                ui.label(egui::RichText::new("A header row can put the columns in any order. Separate several names in a cell with semicolons. Tiers are saved to the roster.").weak());
+
+                if ui.button("📂 Open CSV or TSV").clicked() {
+                    *importError = openPlayerList(ctx, importFile.clone()).err();
+                }
+
+                if let Some(error) = importError {
+                    ui.colored_label(egui::Color32::LIGHT_RED, format!("⚠ {}", error));
+                }
+
+                egui::TextEdit::multiline(importText)
+                    .hint_text("Paste names here")
+                    .code_editor()
+                    .desired_rows(8)
+                    .desired_width(f32::INFINITY)
+                    .ui(ui);
+
+                let players = import::parse(importText);
+
+                if players.is_empty() {
+                    return;
+                }
+
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("ImportPreview").striped(true).show(ui, |ui| {
+
+                        for heading in ["", "Name", "Tier", "Constraints", ""] {
+                            ui.label(egui::RichText::new(heading).strong());
+                        }
+                        ui.end_row();
+
+                        for (index, player) in players.iter().enumerate() {
+
+                            let links: Vec<String> = player.links.iter().map(|(kind, name)| format!("{}: {}", kind.label(), name)).collect();
+                            let problems: Vec<String> = player.problems.iter().map(|problem| problem.to_string()).collect();
+
+                            ui.label((index + 1).to_string());
+                            ui.label(&player.name);
+                            ui.label(player.tier.map_or_else(String::new, |tier| tier.to_string()));
+                            ui.label(links.join("\n"));
+                            ui.colored_label(egui::Color32::LIGHT_RED, problems.join("\n"));
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                let flagged = players.iter().filter(|player| !player.problems.is_empty()).count();
+                let fewest = if *mode == ScheduleMode::Rotation { tableFormat.seats() } else { 4 };
+
+                let problem = if flagged > 0 {
+                    Some(format!("Fix the {} flagged rows to import", flagged))
+                } else if !(fewest..=50).contains(&players.len()) {
+                    Some(format!("A card takes {} to 50 players", fewest))
+                } else if *mode == ScheduleMode::FixedPairs && players.len() % 2 != 0 {
+                    Some(String::from("Pairs need an even number of players"))
+                } else {
+                    None
+                };
+
+                if let Some(problem) = &problem {
+                    ui.colored_label(egui::Color32::LIGHT_RED, problem);
+                }
+
+                if *currentRound > 0 || !scores.rounds.is_empty() {
+                    ui.label(egui::RichText::new("Importing starts a new card, clearing the rounds played and their scores").weak());
+                }
+
+                if ui.add_enabled(problem.is_none(), egui::Button::new(format!("Import {} Players", players.len()))).clicked() {
+                    imported = Some(players);
+                }
+            });
+
+        if let Some(players) = imported {
+
+            // The new card is built before anything is replaced, so a list it can't be made for
+            // leaves the current event as it was.
+            let (_, newOutCount) = tableShape(players.len(), *mode, *leftover, *tableFormat);
+
+            let params = ScheduleParams {
+                playerCount: players.len(),
+                gameCount: fullRoundCount(players.len(), *mode, newOutCount).unwrap_or(*gameCount),
+                seed: *seed,
+                leftover: *leftover,
+                format: *tableFormat,
+                mode: *mode,
+                constraints: import::constraints(&players),
+                keepTables: *keepTables,
+                ..Default::default()
+            };
+
+            match schedule::generate(&params) {
+                Ok(card) => {
+                    *scheduleError = card.repeatedSitOut(&params).map(|warning| warning.to_string());
+                    (*tableCount, *outCount) = (card.tableCount(), card.outCount());
+                    *cardData = card;
+
+                    *playerCount = params.playerCount;
+                    *gameCount = params.gameCount;
+                    *playerNames = players.iter().map(|player| player.name.clone()).collect();
+                    *constraints = params.constraints;
+                    teamNames.clear();
+                    departed.clear();
+                    pins.clear();
+                    *currentRound = 0;
+                    *scores = Scores::default();
+                    *bracket = None;
+                    *optimizer = None;
+                    checkedIn.clear();
+
+                    for player in players.iter().filter(|player| player.tier.is_some()) {
+                        roster.entry(&player.name).tier = player.tier;
+                    }
+
+                    *importError = None;
+                    *importOpen = false;
+                }
+                Err(error) => *importError = Some(error.to_string()),
+            }
+        }
+
+        // Fixed pairs check in together.
+        let unit = if *mode == ScheduleMode::FixedPairs { 2 } else { 1 };
+        checkedIn.resize(*playerCount / unit, false);
+
+        let mut confirmed = false;
+
+        egui::Window::new("✅ Check In")
+            .open(checkInOpen)
+            .show(ctx, |ui| {
+
+                let here = checkedIn.iter().filter(|&&present| present).count();
+                let fewest = if *mode == ScheduleMode::Rotation { tableFormat.seats() } else { 4 };
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} of {} here", here * unit, *playerCount));
+
+                    if ui.button("All Here").clicked() {
+                        checkedIn.iter_mut().for_each(|present| *present = true);
+                    }
+
+                    if ui.button("Nobody Here").clicked() {
+                        checkedIn.iter_mut().for_each(|present| *present = false);
+                    }
+                });
+
+                ui.label(egui::RichText::new("Players who don't show are dropped from tonight's card and kept on the roster").weak());
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for (index, present) in checkedIn.iter_mut().enumerate() {
+
+                        let players: Vec<PlayerId> = (unit * index + 1..=unit * index + unit).collect();
+                        let mut label = formatPlayersVector(players, " & ".to_owned(), true, playerNames.clone());
+
+                        if let Some(name) = teamNames.get(index).filter(|name| unit == 2 && !name.is_empty()) {
+                            label = format!("{}: {}", name, label);
+                        }
+
+                        ui.checkbox(present, label);
+                    }
+                });
+
+                ui.separator();
+
+                if here * unit < fewest {
+                    ui.colored_label(egui::Color32::LIGHT_RED, format!("A card needs at least {} players", fewest));
+                }
+
+                if ui.add_enabled(here * unit >= fewest, egui::Button::new(format!("Start With {} Players", here * unit))).clicked() {
+                    confirmed = true;
+                }
+            });
+
+        if confirmed {
+
+            roster.remember(playerNames.iter().take(*playerCount));
+
+            // From the end down, so the numbers still to be dropped stay put.
+            for index in (0..checkedIn.len()).rev().filter(|&index| !checkedIn[index]) {
+
+                if unit == 2 && index < teamNames.len() {
+                    teamNames.remove(index);
+                }
+
+                for player in (unit * index + 1..=unit * index + unit).rev() {
+
+                    *playerCount -= 1;
+
+                    if player <= playerNames.len() {
+                        playerNames.remove(player - 1);
+                    }
+
+                    shiftConstraints(constraints, player, -1);
+                    shiftPlayers(&mut [], departed, pins, scores, bracket, player, -1);
+                }
+            }
+
+            checkedIn.clear();
+            *optimizer = None;
+            (*tableCount, *outCount) = tableShape(*playerCount, *mode, *leftover, *tableFormat);
+            *gameCount = fullRoundCount(*playerCount, *mode, *outCount).unwrap_or(*gameCount);
+            *checkInOpen = false;
+            regenerate = true;
+        }
+
+        if regenerate {
+
+            departed.retain(|&player| player <= *playerCount);
+            *currentRound = (*currentRound).min(*gameCount);
+
+            // Once a progressive or Swiss event is under way, roster changes wait for the next round.
+            let kept = match mode.roundByRound() && *currentRound > 0 {
+                true => cardData.rounds.len(),
+                false => (*currentRound).min(cardData.rounds.len()),
+            };
+
+            let params = ScheduleParams {
+                playerCount: *playerCount,
+                gameCount: *gameCount,
+                seed: *seed,
+                leftover: *leftover,
+                format: *tableFormat,
+                mode: *mode,
+                constraints: constraints.clone(),
+                keepTables: *keepTables,
+                played: cardData.rounds[..kept].to_vec(),
+                absent: if *mode == ScheduleMode::FixedPairs { Vec::new() } else { departed.clone() },
+                pins: pins.clone(),
+            };
+
+            let previous = std::mem::take(&mut cardData.rounds);
+
+            // Settings that can't be met leave only the played rounds up, with the reason shown
+            // above them, as the rest of the old card was built for a different roster. A card
+            // that sits a pair out together twice is still shown, with a warning.
+            match schedule::generate(&params) {
+                Ok(card) => {
+                    *scheduleError = card.repeatedSitOut(&params).map(|warning| warning.to_string());
+                    *cardData = card;
+                }
+                Err(error) => {
+                    *cardData = Schedule { seed: *seed, rounds: params.played };
+                    *scheduleError = Some(format!("{}. Only the rounds already played are shown", error));
+                }
+            }
+
+            // Scores only carry over for tables that stay as they are.
+            scores.carryOver(&previous, &cardData.rounds);
+
+            (*tableCount, *outCount) = (cardData.tableCount(), cardData.outCount());
+        }
+
         egui::Window::new("🗖 Settings")
             .resizable(false)
             .open(settingsOpen)
@@ -262,18 +1091,496 @@ impl eframe::App for RotatorApp {
 
                 ui.separator();
 
-                ui.heading("Formatting");
-                ui.add_space(5.0);
+                ui.heading("Formatting");
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    ui.label("Separator");
+                    ui.text_edit_singleline(separator)
+                        .context_menu(|ui| {
+                            if ui.button("Set to Default").clicked() {
+                                *separator = String::from("-");
+                            }
+                        });
+                });
+                ui.add(egui::Checkbox::new(displayNames, "Display Names"));
+                ui.add(egui::Checkbox::new(showSeats, "Show Seats & First Dealer"));
+
+                ui.separator();
+
+                ui.heading("Optimizer");
+                ui.add_space(5.0);
+                ui.add(egui::Slider::new(&mut costWeights.repeatPartner, 0.0..=20.0).text("Repeat Partner Cost"));
+                ui.add(egui::Slider::new(&mut costWeights.repeatOpponent, 0.0..=20.0).text("Repeat Opponent Cost"));
+                ui.add(egui::Slider::new(&mut costWeights.unevenSitOut, 0.0..=20.0).text("Uneven Sit-Out Cost"));
+                ui.add(egui::Slider::new(&mut costWeights.tableMove, 0.0..=20.0).text("Table Move Cost"));
+
+                ui.separator();
+
+                ui.heading("Scoring");
+                ui.add_space(5.0);
+                ui.add(egui::Slider::new(target, 5..=25).text("Points to Win"));
+
+                ui.separator();
+
+                ui.heading("Round Timer");
+                ui.add_space(5.0);
+                ui.add(egui::Slider::new(&mut timer.minutes, 1..=60).text("Round Length (min)"));
+                ui.add(egui::Slider::new(&mut timer.warningMinutes, 0..=10).text("Warning (min left)"));
+
+                ui.horizontal(|ui| {
+                    ui.label("When Time Runs Out");
+
+                    egui::ComboBox::from_id_source("OvertimeRule")
+                        .selected_text(timer.overtime.label())
+                        .show_ui(ui, |ui| {
+                            for option in OvertimeRule::ALL {
+                                ui.selectable_value(&mut timer.overtime, option, option.label());
+                            }
+                        });
+                });
+
+                ui.separator();
+
+                ui.heading("Ranking");
+                ui.add_space(5.0);
+                ui.label(egui::RichText::new("Players are compared on each column in turn").weak());
+
+                let mut moved = None;
+                let last = rankingRules.order.len().saturating_sub(1);
+
+                for (index, rank) in rankingRules.order.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(index > 0, egui::Button::new("⬆")).clicked() {
+                            moved = Some(index - 1);
+                        }
+
+                        if ui.add_enabled(index < last, egui::Button::new("⬇")).clicked() {
+                            moved = Some(index);
+                        }
+
+                        ui.label(format!("{}. {}", index + 1, rank.label()));
+                    });
+                }
+
+                if let Some(index) = moved {
+                    rankingRules.order.swap(index, index + 1);
+                }
+
+                ui.add(egui::Checkbox::new(&mut rankingRules.sitOutCredit, "Credit sit-outs at the player's average"));
+        });
+
+        // The round in play: the newest one for cards built from results, otherwise the first not
+        // yet marked played.
+        let roundInPlay = match mode.roundByRound() {
+            true => cardData.rounds.len().saturating_sub(1),
+            false => (*currentRound).min(cardData.rounds.len().saturating_sub(1)),
+        };
+
+        egui::Window::new("⏰ Time!")
+            .open(timeUp)
+            .collapsible(false)
+            .show(ctx, |ui| {
+
+                ui.label(egui::RichText::new(timer.overtime.instruction()).strong());
+                ui.add_space(5.0);
+
+                let Some(round) = cardData.rounds.get(roundInPlay) else { return };
+                let label = |player: PlayerId| formatPlayersVector(vec![player], separator.clone(), *displayNames, playerNames.clone());
+
+                egui::Grid::new("TimeUpScores").striped(true).show(ui, |ui| {
+                    for (tableIndex, table) in round.tables.iter().enumerate().filter(|(_, table)| table.isPartnership()) {
+
+                        let score = scores.tableMut(roundInPlay, tableIndex, 2);
+                        let editable = score.hands.is_empty();
+
+                        ui.label(egui::RichText::new(format!("Table {}", tableIndex + 1)).strong());
+
+                        for (team, points) in table.teams.iter().zip(score.points.iter_mut()) {
+                            ui.label(team.players.iter().map(|&player| label(player)).collect::<Vec<String>>().join(" & "));
+                            ui.add_enabled(editable, egui::TextEdit::singleline(points).hint_text("Points").desired_width(50.0));
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                ui.add_space(5.0);
+
+                if mode.roundByRound() {
+                    ui.label(egui::RichText::new("Use Next Round in the side panel once every table is in").weak());
+                } else if ui.button(format!("✔ Mark Round {} Played", roundInPlay + 1)).clicked() {
+                    *currentRound = (roundInPlay + 1).max(*currentRound);
+                    timer.reset();
+                }
+            });
+
+        if let Some((roundIndex, tableIndex)) = *sheetOpen {
+
+            let mut open = true;
+
+            match cardData.rounds.get(roundIndex).and_then(|round| round.tables.get(tableIndex)).filter(|table| table.isPartnership()) {
+                Some(table) => {
+                    let label = |player: PlayerId| formatPlayersVector(vec![player], separator.clone(), true, playerNames.clone());
+
+                    egui::Window::new(format!("📝 Round {}, Table {}", roundIndex + 1, tableIndex + 1))
+                        .id(egui::Id::new("ScoreSheet"))
+                        .open(&mut open)
+                        .show(ctx, |ui| handSheet(ui, scores.tableMut(roundIndex, tableIndex, 2), table, *target, &label));
+                }
+                None => open = false,
+            }
+
+            if !open {
+                *sheetOpen = None;
+            }
+        }
+
+        egui::Window::new("🥇 Leaderboard")
+            .open(leaderboardOpen)
+            .show(ctx, |ui| {
+
+                let standings = scoring::standings(cardData, scores, *playerCount, rankingRules);
+                let places = scoring::places(&standings, rankingRules);
+
+                if standings.iter().all(|standing| standing.played == 0) {
+                    ui.label("No results yet. Enter each table's points under its teams on the card.");
+                    return;
+                }
+
+                // Credited columns carry fractions, so they get a decimal place.
+                let credited = rankingRules.sitOutCredit && standings.iter().any(|standing| standing.satOut > 0);
+                let show = |standing: &scoring::Standing, rank: scoring::RankBy| match credited {
+                    true => format!("{:.1}", standing.value(rank, rankingRules)),
+                    false => format!("{}", standing.value(rank, rankingRules)),
+                };
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("Leaderboard").striped(true).show(ui, |ui| {
+
+                        for heading in ["#", "Player", "Played", "Sat Out", "Won", "Points", "+/-", "Opp. Wins"] {
+                            ui.label(egui::RichText::new(heading).strong());
+                        }
+                        ui.end_row();
+
+                        for (standing, place) in standings.iter().zip(&places) {
+                            ui.label(place.to_string());
+                            ui.label(formatPlayersVector(vec![standing.player], separator.clone(), *displayNames, playerNames.clone()));
+                            ui.label(standing.played.to_string());
+                            ui.label(standing.satOut.to_string());
+                            ui.label(show(standing, scoring::RankBy::Wins));
+                            ui.label(show(standing, scoring::RankBy::Points));
+                            ui.label(show(standing, scoring::RankBy::Difference));
+                            ui.label(show(standing, scoring::RankBy::OpponentWins));
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                if credited {
+                    ui.label(egui::RichText::new("Sit-outs count as games at the player's own average").weak());
+                }
+
+                let hands = scoring::handStats(cardData, scores, *playerCount, *target);
+
+                if hands.iter().any(|stats| stats.calls > 0) {
+
+                    ui.separator();
+                    ui.label(egui::RichText::new("Hands").strong());
+
+                    egui::Grid::new("LeaderboardHands").striped(true).show(ui, |ui| {
+
+                        for heading in ["Player", "Calls", "Marches", "Loners", "Euchres", "Euchred"] {
+                            ui.label(egui::RichText::new(heading).strong());
+                        }
+                        ui.end_row();
+
+                        for standing in &standings {
+
+                            let stats = &hands[standing.player - 1];
+
+                            ui.label(formatPlayersVector(vec![standing.player], separator.clone(), *displayNames, playerNames.clone()));
+                            for count in [stats.calls, stats.marches, stats.loners, stats.euchres, stats.euchred] {
+                                ui.label(count.to_string());
+                            }
+                            ui.end_row();
+                        }
+                    });
+                }
+            });
+
+        egui::Window::new("👥 Roster")
+            .open(rosterOpen)
+            .show(ctx, |ui| {
+
+                ui.horizontal(|ui| {
+                    ui.label("Search");
+                    ui.text_edit_singleline(rosterFilter);
+                });
+
+                ui.label(egui::RichText::new("Known players are offered in the name boxes as you type").weak());
+                ui.separator();
+
+                let filter = rosterFilter.trim().to_lowercase();
+                let mut removed = None;
+
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("Roster").striped(true).show(ui, |ui| {
+
+                        for heading in ["Name", "Nickname", "Tier", "Notes"] {
+                            ui.label(egui::RichText::new(heading).strong());
+                        }
+                        ui.end_row();
+
+                        for (index, known) in roster.players.iter_mut().enumerate() {
+
+                            if !filter.is_empty() && !known.label().to_lowercase().contains(&filter) {
+                                continue;
+                            }
+
+                            egui::TextEdit::singleline(&mut known.name).hint_text("Name").desired_width(120.0).ui(ui);
+                            egui::TextEdit::singleline(&mut known.nickname).hint_text("Nickname").desired_width(90.0).ui(ui);
+
+                            egui::ComboBox::from_id_source(("RosterTier", index))
+                                .selected_text(known.tier.map_or_else(|| String::from("—"), |tier| tier.to_string()))
+                                .width(40.0)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut known.tier, None, "—");
+                                    for tier in roster::TIERS {
+                                        ui.selectable_value(&mut known.tier, Some(tier), tier.to_string());
+                                    }
+                                });
+
+                            egui::TextEdit::singleline(&mut known.notes).hint_text("Notes").desired_width(160.0).ui(ui);
+
+                            if ui.button("🗑").clicked() {
+                                removed = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                if let Some(index) = removed {
+                    roster.players.remove(index);
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("➕ Add Player").clicked() {
+                        roster.players.push(KnownPlayer::default());
+                        rosterFilter.clear();
+                    }
+
+                    if ui.button("Save Tonight's Names").on_hover_text("Adds everyone named on the card who isn't known yet").clicked() {
+                        roster.remember(playerNames.iter().take(*playerCount));
+                    }
+                });
+            });
+
+        egui::Window::new("🎖 Awards")
+            .open(awardsOpen)
+            .show(ctx, |ui| {
+
+                let given = awards::awards(cardData, scores, *playerCount, *target, departed);
+                awardNames.resize(AwardKind::ALL.len(), String::new());
+
+                egui::Grid::new("Awards").striped(true).show(ui, |ui| {
+                    for (award, name) in given.iter().zip(awardNames.iter_mut()) {
+
+                        ui.vertical(|ui| {
+                            egui::TextEdit::singleline(name).hint_text(award.kind.defaultName()).desired_width(160.0).ui(ui);
+                            ui.label(egui::RichText::new(award.kind.description()).weak());
+                        });
+
+                        match award.winners.is_empty() {
+                            true => ui.label(egui::RichText::new("No winner yet").weak()),
+                            false => ui.label(format!("{}\n{}", awardWinners(award, playerNames), award.detail)),
+                        };
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui.button("Export Certificates").clicked() {
+
+                    if let Some(path) = rfd::FileDialog::new().add_filter("PDF", &["pdf"]).save_file() {
+                        exportAwardsPDF(path, &given, awardNames, playerNames.clone(), cardData.seed);
+                    }
+                }
+            });
+
+        egui::Window::new("🏆 Playoff")
+            .open(playoffOpen)
+            .show(ctx, |ui| {
+
+                let playerLabel = |player: PlayerId| formatPlayersVector(vec![player], separator.clone(), *displayNames, playerNames.clone());
+
+                ui.horizontal(|ui| {
+                    ui.add(egui::Slider::new(playoffSize, 4..=(*playerCount).max(4)).step_by(2.0).text("Players"));
+
+                    if ui.button("Draw from Standings").clicked() {
+
+                        let ranking: Vec<PlayerId> = scoring::standings(cardData, scores, *playerCount, rankingRules).iter()
+                            .map(|standing| standing.player)
+                            .filter(|player| !departed.contains(player))
+                            .collect();
+
+                        match Bracket::new(&ranking, *playoffSize) {
+                            Ok(drawn) => {
+                                *bracket = Some(drawn);
+                                *playoffError = None;
+                            }
+                            Err(error) => *playoffError = Some(error.to_string()),
+                        }
+                    }
+                });
+
+                ui.label(egui::RichText::new("Best plays worst of those drawn, and pairs are seeded by their better player").weak());
+
+                if let Some(error) = playoffError {
+                    ui.colored_label(egui::Color32::LIGHT_RED, error.as_str());
+                }
+
+                let Some(drawn) = bracket else { return };
+
+                ui.separator();
+
+                egui::ScrollArea::both().show(ui, |ui| {
+                    ui.horizontal_top(|ui| {
+                        for stage in 0..drawn.stageCount() {
+                            ui.vertical(|ui| {
+
+                                ui.label(egui::RichText::new(drawn.stageName(stage)).strong());
+
+                                for (index, sides) in drawn.matches(stage).into_iter().enumerate() {
+
+                                    let winner = drawn.winner(stage, index);
+                                    let playing = matches!(sides, [Entrant::Pair(_), Entrant::Pair(_)]);
+
+                                    ui.group(|ui| {
+                                        for (side, entrant) in sides.into_iter().enumerate() {
+                                            ui.horizontal(|ui| {
+
+                                                let text = match entrant {
+                                                    Entrant::Pair(pair) => egui::RichText::new(formatPlayersVector(drawn.pairs[pair].to_vec(), separator.clone(), *displayNames, playerNames.clone())),
+                                                    Entrant::Bye => egui::RichText::new("Bye").weak(),
+                                                    Entrant::Undecided => egui::RichText::new("—").weak(),
+                                                };
+
+                                                ui.label(if winner.map(Entrant::Pair) == Some(entrant) { text.strong() } else { text });
+
+                                                if playing {
+                                                    egui::TextEdit::singleline(&mut drawn.scoreMut(stage, index).points[side]).hint_text("Points").desired_width(50.0).ui(ui);
+                                                }
+                                            });
+                                        }
+                                    });
+                                }
+                            });
+                        }
+
+                        if let Some(champion) = drawn.champion() {
+                            ui.vertical(|ui| {
+                                ui.label(egui::RichText::new("Champions").strong());
+                                ui.label(egui::RichText::new(drawn.pairs[champion].map(playerLabel).join(" & ")).heading());
+                            });
+                        }
+                    });
+                });
+
+                ui.separator();
+                let mut cleared = false;
+
+                ui.horizontal(|ui| {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button("Export").clicked() {
+
+                        if let Some(path) = rfd::FileDialog::new().add_filter("PDF", &["pdf"]).save_file() {
+                            if let Err(error) = exportBracketPDF(path, drawn, separator.clone(), *displayNames, playerNames.clone(), cardData.seed) {
+                                *playoffError = Some(format!("The bracket couldn't be exported: {}", error));
+                            }
+                        }
+                    }
+
+                    cleared = ui.button("Clear").clicked();
+                });
+
+                if cleared {
+                    *bracket = None;
+                }
+            });
+
+        egui::Window::new("📊 Report")
+            .open(reportOpen)
+            .show(ctx, |ui| {
+
+                let report = analysis::analyze(cardData, *playerCount);
+                let playerLabel = |player: PlayerId| formatPlayersVector(vec![player], separator.clone(), *displayNames, playerNames.clone());
+
+                egui::Grid::new("ReportSummary").show(ui, |ui| {
+                    ui.label("Most times partnered");
+                    ui.label(report.maxRepeatPartner.to_string());
+                    ui.end_row();
+
+                    ui.label("Most times opposed");
+                    ui.label(report.maxRepeatOpponent.to_string());
+                    ui.end_row();
+
+                    ui.label("Pairs never partnered");
+                    ui.label(report.neverPartnered.to_string());
+                    ui.end_row();
+
+                    ui.label("Pairs never met");
+                    ui.label(report.neverMet.to_string());
+                    ui.end_row();
+
+                    ui.label("Table moves");
+                    ui.label(report.totalMoves.to_string());
+                    ui.end_row();
+                });
+
+                ui.separator();
+
                 ui.horizontal(|ui| {
-                    ui.label("Separator");
-                    ui.text_edit_singleline(separator)
-                        .context_menu(|ui| {
-                            if ui.button("Set to Default").clicked() {
-                                *separator = String::from("-");
-                            }
-                        });
+                    ui.vertical(|ui| {
+                        ui.label(egui::RichText::new("Partners").strong());
+                        heatmap(ui, &report.partners, &playerLabel);
+                    });
+
+                    ui.vertical(|ui| {
+                        ui.label(egui::RichText::new("Opponents").strong());
+                        heatmap(ui, &report.opponents, &playerLabel);
+                    });
+                });
+
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    egui::Grid::new("ReportPlayers").striped(true).show(ui, |ui| {
+
+                        ui.label(egui::RichText::new("Player").strong());
+                        ui.label(egui::RichText::new("Sat Out").strong());
+                        ui.label(egui::RichText::new("3/5-Hand").strong());
+                        ui.label(egui::RichText::new("Deals First").strong());
+                        ui.label(egui::RichText::new("Moves").strong());
+                        ui.label(egui::RichText::new("Partners").strong());
+                        ui.label(egui::RichText::new("Opponents").strong());
+                        ui.end_row();
+
+                        for player in 1..=report.playerCount {
+                            ui.label(playerLabel(player));
+                            ui.label(report.sitOuts[player - 1].to_string());
+                            ui.label(report.oddTables[player - 1].to_string());
+                            ui.label(report.firstDeals[player - 1].to_string());
+                            ui.label(report.moves[player - 1].to_string());
+                            ui.label(report.distinctPartners[player - 1].to_string());
+                            ui.label(report.distinctOpponents[player - 1].to_string());
+                            ui.end_row();
+                        }
+                    });
                 });
-                ui.add(egui::Checkbox::new(displayNames, "Display Names"));
         });
 
         egui::CentralPanel::default()
@@ -281,6 +1588,11 @@ impl eframe::App for RotatorApp {
                     .fill(*background_color).inner_margin(10.0))
             .show(ctx, |ui| {
 
+            if let Some(error) = scheduleError {
+                ui.colored_label(egui::Color32::from_rgb(230, 90, 90), format!("⚠ {}.", error));
+                ui.add_space(6.0);
+            }
+
             egui::ScrollArea::both().show(ui, |ui| {
 
                 egui::Grid::new("EuchreRotationCard")
@@ -290,7 +1602,7 @@ impl eframe::App for RotatorApp {
                 .min_col_width(10.0)
                 .show(ui, |ui| {
 
-                        for row in 0..=*gameCount {
+                        for row in 0..=cardData.rounds.len() {
                                 for col in 0..=*tableCount {
 
                                     match row {
@@ -310,21 +1622,88 @@ impl eframe::App for RotatorApp {
                                         _ => {
                                             match col {
                                                 0 => {
-                                                    ui.label(egui::RichText::new(format!("{}", row)).font(self.font_id.clone()).strong());
+                                                    let played = if row <= *currentRound { "✔ " } else { "" };
+                                                    let pinned = if pins.iter().any(|pin| matches!(pin, Pin::Round { round, .. } if *round == row - 1)) { " 📌" } else { "" };
+                                                    ui.label(egui::RichText::new(format!("{}{}{}", played, row, pinned)).font(self.font_id.clone()).strong());
                                                 }
                                                 _ => {
+                                                    // Played rounds can have more tables than the ones built after them.
+                                                    let Some(table) = cardData.rounds[row - 1].tables.get(col - 1) else {
+                                                        ui.add(egui::Separator::vertical(egui::Separator::default()));
+                                                        ui.label("");
+                                                        ui.label("");
+                                                        ui.label("");
+                                                        continue;
+                                                    };
+
+                                                    let mut marks = if *keepTables { moveMarks(cardData, row - 1, *playerCount) } else { vec![""; *playerCount] };
+
+                                                    for pin in pins.iter() {
+                                                        if let Pin::Seat { round, player, .. } = pin {
+                                                            if *round == row - 1 && *player <= marks.len() {
+                                                                marks[player - 1] = "📌";
+                                                            }
+                                                        }
+                                                    }
+
+                                                    let (left, mut middle, right) = formatTable(table, separator.clone(), *displayNames, playerNames.clone(), pairNames(*mode, teamNames), *showSeats, &marks);
+
+                                                    if pins.iter().any(|pin| matches!(pin, Pin::Table { round, table, .. } if *round == row - 1 && *table == col - 1)) {
+                                                        middle += " 📌";
+                                                    }
+
+                                                    // Each team gets a box for its points, flagged when the entry doesn't add up.
+                                                    // The boxes edit a copy, so tables nobody has scored yet take no room.
+                                                    let mut score = table.isPartnership().then(|| {
+                                                        let mut score = scores.table(row - 1, col - 1).cloned().unwrap_or_default();
+                                                        score.points.resize(2, String::new());
+                                                        score
+                                                    });
+                                                    let typed = score.as_ref().map(|score| score.points.clone());
+                                                    let problem = score.as_ref().and_then(|score| score.problem(*target, row <= *currentRound)).map(|error| error.to_string());
+                                                    let sheet = score.as_ref().map_or(false, |score| !score.hands.is_empty());
+                                                    let (leftPoints, rightPoints) = match score.as_mut() {
+                                                        Some(score) => {
+                                                            let (leftPoints, rightPoints) = score.points.split_at_mut(1);
+                                                            (Some(&mut leftPoints[0]), Some(&mut rightPoints[0]))
+                                                        }
+                                                        None => (None, None),
+                                                    };
+
                                                     ui.add(egui::Separator::vertical(egui::Separator::default()));
-                                                    ui.label(egui::RichText::new(format!("{}", formatPlayersTuple(cardData[row - 1].0[col - 1].0, separator.clone(), *displayNames, playerNames.clone()))).font(self.font_id.clone()));
-                                                    ui.centered_and_justified(|ui| {
-                                                        ui.label(egui::RichText::new("vs").font(self.font_id.clone()).weak())});
-                                                    ui.label(egui::RichText::new(format!("{}", formatPlayersTuple(cardData[row - 1].0[col - 1].1, separator.clone(), *displayNames, playerNames.clone()))).font(self.font_id.clone()));
+                                                    let response = teamCell(ui, egui::RichText::new(left).font(self.font_id.clone()), leftPoints, problem.as_deref(), !sheet)
+                                                        | ui.centered_and_justified(|ui| {
+                                                            ui.label(egui::RichText::new(middle).font(self.font_id.clone()).weak())}).inner
+                                                        | teamCell(ui, egui::RichText::new(right).font(self.font_id.clone()), rightPoints, problem.as_deref(), !sheet);
+
+                                                    if let Some(score) = score.filter(|score| Some(&score.points) != typed.as_ref()) {
+                                                        scores.tableMut(row - 1, col - 1, 2).points = score.points;
+                                                    }
+
+                                                    // Played rounds are already fixed, and fixed pairs keep their round-robin.
+                                                    let pinnable = row > *currentRound && *mode == ScheduleMode::Rotation;
+
+                                                    if pinnable || table.isPartnership() {
+                                                        let label = |player: PlayerId| formatPlayersVector(vec![player], separator.clone(), true, playerNames.clone());
+
+                                                        response.context_menu(|ui| {
+                                                            if table.isPartnership() && ui.button("📝 Score Hand by Hand").clicked() {
+                                                                *sheetOpen = Some((row - 1, col - 1));
+                                                                ui.close_menu();
+                                                            }
+
+                                                            if pinnable {
+                                                                pinMenu(ui, pins, &cardData.rounds[row - 1], row - 1, col - 1, &label);
+                                                            }
+                                                        });
+                                                    }
                                                 }
                                             }
                                         }
                                     }
                                 }
 
-                                if *playerCount % 4 != 0 {
+                                if *outCount > 0 {
 
                                     ui.add_space(5.0);
                                     match row {
@@ -335,7 +1714,7 @@ impl eframe::App for RotatorApp {
                                         }
                                         _ => {
                                             ui.add(egui::Separator::vertical(egui::Separator::default()));
-                                            ui.label(egui::RichText::new(format!("{}", formatPlayersVector(cardData[row - 1].1.clone(), separator.clone(), *displayNames, playerNames.clone()))).font(self.font_id.clone()));
+                                            ui.label(egui::RichText::new(formatTeam(&Team { players: cardData.rounds[row - 1].out.clone() }, separator.clone(), *displayNames, playerNames.clone(), pairNames(*mode, teamNames))).font(self.font_id.clone()));
                                             ui.add(egui::Separator::vertical(egui::Separator::default()));
                                         }
                                     }
@@ -347,191 +1726,534 @@ impl eframe::App for RotatorApp {
             });
         });
 
+        if *timerFullScreen {
+            egui::Area::new("TimerFullScreen")
+                .order(egui::Order::Foreground)
+                .fixed_pos(egui::Pos2::ZERO)
+                .show(ctx, |ui| {
+
+                    let screen = ctx.input().screen_rect();
+                    let response = ui.allocate_rect(screen, egui::Sense::click());
+                    let color = timerColor(ui, timer.warning(now));
+                    let painter = ui.painter();
+
+                    painter.rect_filled(screen, 0.0, ui.visuals().extreme_bg_color);
+                    painter.text(screen.center(), egui::Align2::CENTER_CENTER, timer.display(now), egui::FontId::monospace(screen.height() * 0.4), color);
+
+                    let footer = if *timeUp { timer.overtime.instruction() } else { "Click anywhere to close" };
+                    painter.text(screen.center_bottom() - Vec2::new(0.0, 40.0), egui::Align2::CENTER_BOTTOM, footer, egui::FontId::proportional(24.0), ui.visuals().weak_text_color());
+
+                    if response.clicked() {
+                        *timerFullScreen = false;
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        frame.set_fullscreen(false);
+                    }
+                });
+        }
     }
 }
 
-fn shuffle(playerCount: usize, tableCount: usize, gameCount: usize, outCount: usize) -> Vec<(Vec<((usize, usize), (usize, usize))>, Vec<usize>)> {
+/// Draws a player×player count matrix as shaded cells, with the exact count on hover.
+fn heatmap(ui: &mut egui::Ui, counts: &[Vec<usize>], playerLabel: &dyn Fn(PlayerId) -> String) {
 
-    let mut full: Vec<(Vec<((usize, usize), (usize, usize))>, Vec<usize>)> = Vec::with_capacity(gameCount);
+    let playerCount = counts.len();
+    let max = counts.iter().flatten().copied().max().unwrap_or(0).max(1);
+    let cell = (240.0 / playerCount.max(1) as f32).clamp(4.0, 16.0);
 
-    let mut game: Vec<((usize, usize), (usize, usize))> = Vec::with_capacity(tableCount);
-    let mut out: Vec<usize> = Vec::with_capacity(outCount);
+    let (response, painter) = ui.allocate_painter(Vec2::splat(cell * playerCount as f32), egui::Sense::hover());
+    let origin = response.rect.min;
 
-    let mut players: Vec<usize>;
-    let mut usedPartners: Vec<(usize, usize)> = Vec::new();
-    let mut usedNeighbors: Vec<(usize, usize)> = Vec::new(); 
+    for (a, row) in counts.iter().enumerate() {
+        for (b, &count) in row.iter().enumerate() {
 
-    for gameIndex in 0..gameCount {
+            if a == b {
+                continue;
+            }
 
-        game.clear();
-        out.clear();
-        players = (1..=playerCount).map(usize::from).collect();
+            let rect = egui::Rect::from_min_size(origin + Vec2::new(b as f32 * cell, a as f32 * cell), Vec2::splat(cell - 1.0));
+            painter.rect_filled(rect, 0.0, heatColor(count, max));
+        }
+    }
 
-        match outCount {
-            0 => out.clear(),
-            1 => {
-                out.push(players.remove(uWrap(gameIndex, players.len())));
-            }
-            2 => {
-                if uWrap(gameIndex, players.len()) <= iWrap(-(gameIndex as isize + 1), players.len()) {
-                    out.push(players.remove(uWrap(gameIndex, players.len())));
-                    out.push(players.remove(iWrap(-(gameIndex as isize + 1), players.len())));
-                } else {
-                    out.push(players.remove(uWrap(gameIndex, players.len())));
-                    out.push(players.remove(iWrap(-(gameIndex as isize), players.len())));
-                }
+    if let Some(pos) = response.hover_pos() {
+
+        let a = ((pos.y - origin.y) / cell) as usize;
+        let b = ((pos.x - origin.x) / cell) as usize;
+
+        if a < playerCount && b < playerCount && a != b {
+            response.on_hover_text(format!("{} & {}: {}", playerLabel(a + 1), playerLabel(b + 1), counts[a][b]));
+        }
+    }
+}
+
+/// Grey for pairs that never met, then blue through red as the count approaches `max`.
+fn heatColor(count: usize, max: usize) -> egui::Color32 {
+
+    if count == 0 {
+        return egui::Color32::from_gray(60);
+    }
+
+    let t = count as f32 / max as f32;
+    egui::Color32::from_rgb((60.0 + 195.0 * t) as u8, (140.0 - 60.0 * t) as u8, (220.0 - 170.0 * t) as u8)
+}
+
+/// How many optimizer swaps to try between repaints, small enough to keep the UI responsive.
+const OPTIMIZE_STEPS_PER_FRAME: usize = 2_000;
+
+/// Renumbers constraints after a player is inserted (`delta` of 1) or deleted (`delta` of -1) at
+/// `player`, dropping any that named a deleted player.
+fn shiftConstraints(constraints: &mut Vec<Constraint>, player: PlayerId, delta: isize) {
+
+    if delta < 0 {
+        constraints.retain(|constraint| constraint.a != player && constraint.b != player);
+    }
+
+    for constraint in constraints.iter_mut() {
+        for id in [&mut constraint.a, &mut constraint.b] {
+            *id = shifted(*id, player, delta);
+        }
+    }
+}
+
+/// Renumbers the played rounds, departed players, pins, hand callers and playoff pairs the same
+/// way as `shiftConstraints`. Only players who never played are deleted, so nobody drops out of
+/// a played round, but pins and hands naming them go, and so does a bracket they were drawn into.
+fn shiftPlayers(played: &mut [Round], departed: &mut Vec<PlayerId>, pins: &mut Vec<Pin>, scores: &mut Scores, bracket: &mut Option<Bracket>, player: PlayerId, delta: isize) {
+
+    for round in played {
+        round.relabel(|id| shifted(id, player, delta));
+    }
+
+    pins.retain(|pin| delta > 0 || !pin.players().contains(&player));
+
+    for pin in pins.iter_mut() {
+        pin.relabel(|id| shifted(id, player, delta));
+    }
+
+    departed.retain(|&id| delta > 0 || id != player);
+
+    for id in departed.iter_mut() {
+        *id = shifted(*id, player, delta);
+    }
+
+    for score in scores.rounds.iter_mut().flatten() {
+        score.hands.retain(|hand| delta > 0 || hand.caller != player);
+    }
+
+    scores.relabel(|id| shifted(id, player, delta));
+
+    if delta < 0 && bracket.as_ref().map_or(false, |drawn| drawn.pairs.iter().flatten().any(|&id| id == player)) {
+        *bracket = None;
+    }
+
+    if let Some(drawn) = bracket {
+        drawn.relabel(|id| shifted(id, player, delta));
+    }
+}
+
+/// One side of a table on the card, with a box under it for the team's points when `points` is
+/// given. A `problem` with the table's score turns the box red and explains itself on hover,
+/// and the box is read-only unless `editable`, such as while a score sheet adds up the points.
+fn teamCell(ui: &mut egui::Ui, text: egui::RichText, points: Option<&mut String>, problem: Option<&str>, editable: bool) -> egui::Response {
+
+    match points {
+        Some(points) => ui.vertical(|ui| {
+            let label = ui.label(text);
+            let mut hint = egui::RichText::new("Points");
+            let mut entry = egui::TextEdit::singleline(points).desired_width(50.0);
+
+            if let Some(problem) = problem {
+                hint = hint.color(egui::Color32::LIGHT_RED);
+                entry = entry.text_color(egui::Color32::LIGHT_RED);
             }
-            3 => {
-                if uWrap(gameIndex + 1, players.len()) <= iWrap(-(gameIndex as isize + 1), players.len()) {
-                    out.push(players.remove(uWrap(gameIndex, players.len())));
-                    out.push(players.remove(uWrap(gameIndex, players.len())));
-                    out.push(players.remove(iWrap(-(gameIndex as isize + 1), players.len())));
-                } else {
-                    out.push(players.remove(uWrap(gameIndex, players.len())));
-                    out.push(players.remove(uWrap(gameIndex, players.len())));
-                    out.push(players.remove(iWrap(-(gameIndex as isize), players.len())));
-                }
+
+            let response = ui.add_enabled(editable, entry.hint_text(hint));
+
+            if let Some(problem) = problem {
+                response.on_hover_text(problem);
             }
-            _ => panic!("Something went wrong, outCount is not valid")
+
+            label
+        }).inner,
+        None => ui.label(text),
+    }
+}
+
+/// Asks for a player list file and hands its text to `loaded`.
+#[cfg(not(target_arch = "wasm32"))]
+fn openPlayerList(_ctx: &egui::Context, loaded: Rc<RefCell<Option<String>>>) -> Result<(), String> {
+
+    if let Some(path) = rfd::FileDialog::new().add_filter("Player List", &["csv", "tsv", "txt"]).pick_file() {
+        *loaded.borrow_mut() = Some(fs::read_to_string(path).map_err(|error| error.to_string())?);
+    }
+
+    Ok(())
+}
+
+/// Asks for a player list file and hands its text to `loaded` once the browser has read it.
+#[cfg(target_arch = "wasm32")]
+fn openPlayerList(ctx: &egui::Context, loaded: Rc<RefCell<Option<String>>>) -> Result<(), String> {
+
+    let ctx = ctx.clone();
+
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Some(file) = rfd::AsyncFileDialog::new().add_filter("Player List", &["csv", "tsv", "txt"]).pick_file().await {
+            *loaded.borrow_mut() = Some(String::from_utf8_lossy(&file.read().await).into_owned());
+            ctx.request_repaint();
         }
-    
-        for tableIndex in 0..tableCount {
+    });
+
+    Ok(())
+}
 
-            let mut pAA = rand::thread_rng().gen_range(0..players.len());
-            let mut pAB = rand::thread_rng().gen_range(0..players.len());
+/// A player name box that offers matching players from the roster while it has focus.
+fn nameField(ui: &mut egui::Ui, name: &mut String, hint: String, width: Option<f32>, roster: &Roster) -> egui::Response {
 
-            let mut teamAChecks = 50;
+    let mut edit = egui::TextEdit::singleline(name).hint_text(hint);
 
-            while pAA == pAB || (teamAChecks >= 0 && usedPartners.contains(&(players[pAA], players[pAB]))) {
-                
-                pAA = rand::thread_rng().gen_range(0..players.len());
-                pAB = rand::thread_rng().gen_range(0..players.len());
-                teamAChecks -= 1;
-            }
+    if let Some(width) = width {
+        edit = edit.desired_width(width);
+    }
+
+    let response = edit.ui(ui);
+    let popup = response.id.with("RosterSuggestions");
+    let suggestions = roster.suggestions(name);
+
+    if suggestions.is_empty() {
+        if ui.memory().is_popup_open(popup) {
+            ui.memory().close_popup();
+        }
+    } else if response.has_focus() {
+        ui.memory().open_popup(popup);
+    }
 
-            let teamA = (players[pAA], players[pAB]);
+    egui::popup_below_widget(ui, popup, &response, |ui| {
 
-            usedPartners.push((teamA.0, teamA.1));
-            usedPartners.push((teamA.1, teamA.0));
+        ui.set_min_width(150.0);
 
-            if pAB > pAA {
-                pAB -= 1;
+        for known in suggestions {
+            if ui.selectable_label(false, known.label()).clicked() {
+                *name = known.name.clone();
             }
-            players.remove(pAA);
-            players.remove(pAB);
+        }
+    });
+
+    response
+}
+
+/// Orange for a round timer inside its warning time, otherwise the usual text colour.
+fn timerColor(ui: &egui::Ui, warning: bool) -> egui::Color32 {
+    match warning {
+        true => egui::Color32::from_rgb(255, 140, 0),
+        false => ui.visuals().text_color(),
+    }
+}
+
+/// Hand-by-hand score sheet for a partnership table. The table's points follow the hands, and
+/// no more can be added once a team reaches `target`.
+fn handSheet(ui: &mut egui::Ui, score: &mut TableScore, table: &Table, target: u32, label: &dyn Fn(PlayerId) -> String) {
+
+    let teamLabel = |team: usize| table.teams[team].players.iter().map(|&player| label(player)).collect::<Vec<String>>().join(" & ");
+    let players = table.players();
+    let mut removed = None;
+    let mut totals = [0; 2];
 
-            let mut pBA = rand::thread_rng().gen_range(0..players.len());
-            let mut pBB = rand::thread_rng().gen_range(0..players.len());
+    egui::Grid::new("HandSheet").striped(true).show(ui, |ui| {
 
-            let mut teamBChecks = 50;
-            let mut neighborCheckThreshold = 20;
+        ui.label(egui::RichText::new("#").strong());
+        ui.label(egui::RichText::new("Called By").strong());
+        ui.label(egui::RichText::new("Result").strong());
+        ui.label(egui::RichText::new(teamLabel(0)).strong());
+        ui.label(egui::RichText::new(teamLabel(1)).strong());
+        ui.end_row();
 
-            while pBA == pBB || (neighborCheckThreshold >= 0 && (usedNeighbors.contains(&(teamA.0, players[pBA]))
-                                                                || usedNeighbors.contains(&(players[pBA], teamA.1))
-                                                                || usedNeighbors.contains(&(teamA.1, players[pBB]))
-                                                                || usedNeighbors.contains(&(players[pBB], teamA.0)))) {
+        for (index, hand) in score.hands.iter_mut().enumerate() {
+
+            let over = totals.iter().any(|&points| points >= target);
+
+            ui.label((index + 1).to_string());
+
+            egui::ComboBox::from_id_source(("HandCaller", index))
+                .selected_text(label(hand.caller))
+                .show_ui(ui, |ui| {
+                    for &player in &players {
+                        ui.selectable_value(&mut hand.caller, player, label(player));
+                    }
+                });
+
+            egui::ComboBox::from_id_source(("HandResult", index))
+                .selected_text(hand.result.label())
+                .show_ui(ui, |ui| {
+                    for option in HandResult::ALL {
+                        ui.selectable_value(&mut hand.result, option, format!("{} ({})", option.label(), option.points()));
+                    }
+                });
 
-                while pBA == pBB || (teamBChecks >= 0 && usedPartners.contains(&(players[pBA], players[pBB]))) {
-                    
-                    pBA = rand::thread_rng().gen_range(0..players.len());
-                    pBB = rand::thread_rng().gen_range(0..players.len());
-                    teamBChecks -= 1;
+            // Hands left over after an edit ended the game earlier stay listed but don't count.
+            match (over, hand.scoringTeam(table)) {
+                (false, Some(team)) => {
+                    totals[team] += hand.result.points();
+                    ui.label(totals[0].to_string());
+                    ui.label(totals[1].to_string());
                 }
+                _ => {
+                    ui.label(egui::RichText::new("—").weak());
+                    ui.label(egui::RichText::new("—").weak());
+                }
+            }
 
-                neighborCheckThreshold -= 1;
+            if ui.button("🗑").clicked() {
+                removed = Some(index);
             }
+            ui.end_row();
+        }
+    });
 
-            let teamB = (players[pBA], players[pBB]);
+    if let Some(index) = removed {
+        score.hands.remove(index);
+    }
 
-            usedNeighbors.push((teamA.0, teamB.0));
-            usedNeighbors.push((teamB.0, teamA.0));
-            usedNeighbors.push((teamB.0, teamA.1));
-            usedNeighbors.push((teamA.1, teamB.0));
-            usedNeighbors.push((teamA.1, teamB.1));
-            usedNeighbors.push((teamB.1, teamA.1));
-            usedNeighbors.push((teamB.1, teamA.0));
-            usedNeighbors.push((teamA.0, teamB.1));
+    let (totals, finished) = score.handTotals(table, target);
 
-            usedPartners.push((teamB.0, teamB.1));
-            usedPartners.push((teamB.1, teamB.0));
+    match totals.iter().position(|&points| points >= target) {
+        Some(winner) if finished => {
+            ui.label(egui::RichText::new(format!("{} win {}-{}", teamLabel(winner), totals[winner], totals[1 - winner])).strong());
+        }
+        _ => {
+            if ui.button("➕ Add Hand").clicked() {
+                // The deal passes left every hand, and the first say goes to the dealer's left.
+                let seating = table.seating();
+                let caller = seating[(table.dealer + score.hands.len() + 1) % seating.len()];
 
-            if pBB > pBA {
-                pBB -= 1;
+                score.hands.push(Hand { caller, result: HandResult::Made });
             }
-            players.remove(pBA);
-            players.remove(pBB);
 
-            game.push((teamA, teamB));
+            ui.label(egui::RichText::new(format!("First to {} wins", target)).weak());
         }
+    }
 
-        full.insert(gameIndex, (game.clone(), out.clone()));
+    if !score.hands.is_empty() {
+        score.points = totals.iter().map(|points| points.to_string()).collect();
     }
+}
 
-    let mut rng = StepRng::new(2, 13);
-    let mut irs = Irs::default();
-    irs.shuffle(&mut full, &mut rng);
+/// Right-click menu on a table in the grid for pinning its round, the table itself, or one of
+/// its players, as the card shows them now.
+fn pinMenu(ui: &mut egui::Ui, pins: &mut Vec<Pin>, round: &Round, roundIndex: usize, tableIndex: usize, label: &dyn Fn(PlayerId) -> String) {
 
-    full
+    togglePin(ui, pins, format!("Round {}", roundIndex + 1),
+        |pin| matches!(pin, Pin::Round { round, .. } if *round == roundIndex),
+        || Pin::Round { round: roundIndex, content: round.clone() });
+
+    togglePin(ui, pins, format!("Table {}", tableIndex + 1),
+        |pin| matches!(pin, Pin::Table { round, table, .. } if *round == roundIndex && *table == tableIndex),
+        || Pin::Table { round: roundIndex, table: tableIndex, content: round.tables[tableIndex].clone() });
+
+    ui.separator();
+
+    for player in round.tables[tableIndex].players() {
+        togglePin(ui, pins, format!("{} to Table {}", label(player), tableIndex + 1),
+            |pin| matches!(pin, Pin::Seat { round, player: pinned, .. } if *round == roundIndex && *pinned == player),
+            || Pin::Seat { round: roundIndex, table: tableIndex, player });
+    }
 }
 
-fn formatPlayersTuple(playerTup: (usize, usize), separator: String, displayNames: bool, playerNames: Vec<String>) -> String{
+fn togglePin(ui: &mut egui::Ui, pins: &mut Vec<Pin>, name: String, matches: impl Fn(&Pin) -> bool, pin: impl FnOnce() -> Pin) {
 
-    let mut output: String = String::new();
+    let pinned = pins.iter().any(&matches);
+    let text = if pinned { format!("Unpin {}", name) } else { format!("📌 Pin {}", name) };
 
-    if displayNames {
-        let mut playerA = playerNames[playerTup.0 - 1].clone();
-        let mut playerB = playerNames[playerTup.1 - 1].clone();
+    if ui.button(text).clicked() {
 
-        if playerA.is_empty() {
-            playerA = format!("Player {}", playerTup.0);
+        if pinned {
+            pins.retain(|existing| !matches(existing));
+        } else {
+            pins.push(pin());
         }
 
-        if playerB.is_empty() {
-            playerB = format!("Player {}", playerTup.1);
-        }
+        ui.close_menu();
+    }
+}
 
-        output = format!("{}{}{}", playerA, separator, playerB);
-    } else {
-        output = format!("{}{}{}", playerTup.0, separator, playerTup.1);
+fn shifted(id: PlayerId, player: PlayerId, delta: isize) -> PlayerId {
+    match id >= player {
+        true => (id as isize + delta) as PlayerId,
+        false => id,
     }
+}
 
-    output
+/// A drop-down of every player in the event, labelled by name where one is set.
+fn playerPicker(ui: &mut egui::Ui, id: &str, player: &mut PlayerId, playerCount: usize, playerNames: &[String]) {
+
+    let label = |player: PlayerId| match playerNames.get(player - 1).filter(|name| !name.is_empty()) {
+        Some(name) => name.clone(),
+        None => format!("Player {}", player),
+    };
+
+    egui::ComboBox::from_id_source(id)
+        .selected_text(label(*player))
+        .show_ui(ui, |ui| {
+            for option in 1..=playerCount {
+                ui.selectable_value(player, option, label(option));
+            }
+        });
+}
+
+/// Rounds for a whole card of `playerCount` players: every pair once in fixed pairs, and a full
+/// rotation otherwise. Cards built round by round keep whatever length was asked for.
+fn fullRoundCount(playerCount: usize, mode: ScheduleMode, outCount: usize) -> Option<usize> {
+    match mode {
+        ScheduleMode::FixedPairs => Some(schedule::roundRobinLength(playerCount / 2)),
+        ScheduleMode::Rotation if outCount == 0 => Some(playerCount - 1),
+        ScheduleMode::Rotation => Some(playerCount),
+        ScheduleMode::Progressive | ScheduleMode::Swiss => None,
+    }
+}
+
+/// Table and sit-out columns the card needs for `playerCount` players.
+fn tableShape(playerCount: usize, mode: ScheduleMode, leftover: Leftover, format: TableFormat) -> (usize, usize) {
+
+    let params = ScheduleParams { playerCount, leftover, format, mode, ..Default::default() };
+    (params.tableCount(), params.outCount())
+}
+
+/// A team's players, or its team name when it is a named pair. `teamNames` is left empty
+/// outside fixed-pair mode.
+fn formatTeam(team: &Team, separator: String, displayNames: bool, playerNames: Vec<String>, teamNames: Vec<String>) -> String {
+
+    match teamName(team, displayNames, &teamNames) {
+        Some(name) => name,
+        None => formatPlayersVector(team.players.clone(), separator, displayNames, playerNames),
+    }
+}
+
+fn teamName(team: &Team, displayNames: bool, teamNames: &[String]) -> Option<String> {
+
+    if !displayNames || !schedule::isPair(&team.players) {
+        return None;
+    }
+
+    teamNames.get(team.players[0].max(team.players[1]) / 2 - 1).filter(|name| !name.is_empty()).cloned()
+}
+
+/// Like `formatTeam`, but with each player's seat and a `*` on whoever deals first, e.g.
+/// "N 3* - S 7", or "Aces (N*/S)" for a named pair.
+fn formatSeatedTeam(table: &Table, team: &Team, separator: String, displayNames: bool, playerNames: Vec<String>, teamNames: Vec<String>, marks: &[&str]) -> String {
+
+    let seat = |player: PlayerId| {
+        let dealer = if table.dealerPlayer() == Some(player) { "*" } else { "" };
+        format!("{}{}", table.seatName(player).unwrap_or_default(), dealer)
+    };
+
+    if let Some(name) = teamName(team, displayNames, &teamNames) {
+        let seats: Vec<String> = team.players.iter().map(|&player| seat(player)).collect();
+        return format!("{}{} ({})", mark(marks, team.players[0]), name, seats.join("/"));
+    }
+
+    team.players.iter()
+        .map(|&player| format!("{} {}{}", seat(player), mark(marks, player), formatPlayersVector(vec![player], separator.clone(), displayNames, playerNames.clone())))
+        .collect::<Vec<String>>()
+        .join(&separator)
+}
+
+/// "→" for each player who changed tables coming into round `roundIndex` and "•" for each who
+/// stayed, indexed like `playerNames`. Players with nothing to compare against get no mark.
+fn moveMarks(schedule: &Schedule, roundIndex: usize, playerCount: usize) -> Vec<&'static str> {
+
+    (1..=playerCount)
+        .map(|player| match analysis::moved(schedule, roundIndex, player) {
+            Some(true) => "→",
+            Some(false) => "•",
+            None => "",
+        })
+        .collect()
+}
+
+fn mark<'a>(marks: &[&'a str], player: PlayerId) -> &'a str {
+
+    marks.get(player - 1).copied().unwrap_or_default()
+}
+
+/// Team names to show on the card, which only apply while pairs are fixed.
+fn pairNames(mode: ScheduleMode, teamNames: &[String]) -> Vec<String> {
+
+    match mode {
+        ScheduleMode::FixedPairs => teamNames.to_vec(),
+        ScheduleMode::Rotation | ScheduleMode::Progressive | ScheduleMode::Swiss => Vec::new(),
+    }
+}
+
+/// The two sides of a table and what goes between them on the card. Three- and five-handed
+/// tables list everyone on the left, in seating order, with the table size in the middle. Each
+/// player is prefixed with their entry in `marks`, which may be left empty.
+fn formatTable(table: &Table, separator: String, displayNames: bool, playerNames: Vec<String>, teamNames: Vec<String>, showSeats: bool, marks: &[&str]) -> (String, String, String) {
+
+    let label = |player: PlayerId| format!("{}{}", mark(marks, player), formatPlayersVector(vec![player], separator.clone(), displayNames, playerNames.clone()));
+
+    if table.isPartnership() {
+
+        let side = |team: &Team| match (showSeats, teamName(team, displayNames, &teamNames)) {
+            (true, _) => formatSeatedTeam(table, team, separator.clone(), displayNames, playerNames.clone(), teamNames.clone(), marks),
+            (false, Some(name)) => format!("{}{}", mark(marks, team.players[0]), name),
+            (false, None) => team.players.iter().map(|&player| label(player)).collect::<Vec<String>>().join(&separator),
+        };
+
+        return (side(&table.teams[0]), String::from("vs"), side(&table.teams[1]));
+    }
+
+    let order = if showSeats { table.seating() } else { table.players() };
+
+    let players: Vec<String> = order.iter()
+        .map(|&player| {
+            let dealer = if showSeats && table.dealerPlayer() == Some(player) { "*" } else { "" };
+            format!("{}{}", label(player), dealer)
+        })
+        .collect();
+
+    (players.join(&separator), format!("{}-hand", players.len()), String::new())
 }
 
-fn formatPlayersVector(playerVec: Vec<usize>, separator: String, displayNames: bool, playerNames: Vec<String>) -> String{
+fn formatPlayersVector(playerVec: Vec<PlayerId>, separator: String, displayNames: bool, playerNames: Vec<String>) -> String{
 
     let mut output: String = String::new();
 
-    for index in 0..playerVec.len() {
+    for (index, &player) in playerVec.iter().enumerate() {
         
         if index != 0 {
             output += &separator;
         }
 
         if displayNames {
-            let mut name = playerNames[playerVec[index] - 1].clone();
-
-            if name.is_empty() {
-                name = format!("Player {}", playerVec[index]);
+            match playerNames.get(player - 1).filter(|name| !name.is_empty()) {
+                Some(name) => output += name,
+                None => output += &format!("Player {}", player),
             }
-
-            output += &playerNames[playerVec[index] - 1];
         } else {
-            output += &playerVec[index].to_string();
+            output += &player.to_string();
         }
     }
 
     output
 }
 
-fn exportPDF(path: PathBuf,
-            tableCount: usize,
-            outCount: usize,
-            gameCount: usize,
-            separator: String,
-            displayNames: bool,
-            cardData: Vec<(Vec<((usize, usize), (usize, usize))>, Vec<usize>)>,
-            playerNames: Vec<String>) {
+/// How the card is laid out and labelled when exported.
+struct ExportOptions {
+    tableCount: usize,
+    outCount: usize,
+    playerCount: usize,
+    separator: String,
+    displayNames: bool,
+    playerNames: Vec<String>,
+    teamNames: Vec<String>,
+    showSeats: bool,
+    keepTables: bool,
+}
+
+/// Exports the rounds of `cardData` in `rounds`, numbered as they are on the card.
+fn exportPDF(path: PathBuf, cardData: &Schedule, rounds: Range<usize>, options: &ExportOptions) {
+
+    let ExportOptions { tableCount, outCount, playerCount, ref separator, displayNames, ref playerNames, ref teamNames, showSeats, keepTables } = *options;
 
     let font_family = genpdf::fonts::from_files("./src/fonts", "LiberationSans", None)
         .expect("Failed to load font family for pdf export");
@@ -551,8 +2273,16 @@ fn exportPDF(path: PathBuf,
     let mut paper_size: genpdf::Size = genpdf::Size::new(10, 10);
     let mut column_weights = vec![8];
 
+    // Team columns widen with the most players any one cell has to list.
+    let widest = cardData.rounds[rounds.clone()].iter()
+        .flat_map(|round| round.tables.iter())
+        .map(|table| if table.isPartnership() { table.teams[0].players.len() } else { table.players().len() })
+        .max()
+        .unwrap_or(2)
+        .max(2);
+
     for table in 0..tableCount {
-        column_weights.append(&mut vec![1,10,8,10]);
+        column_weights.append(&mut vec![1, 5 * widest, 8, 5 * widest]);
     }
 
     if outCount > 0 {
@@ -565,8 +2295,10 @@ fn exportPDF(path: PathBuf,
 
     let style = genpdf::style::Style::new();
 
-    for gameId in 0..=gameCount {
+    for gameId in 0..=rounds.len() {
 
+        // Row 0 is the header, and the rows after it are the rounds in `rounds`.
+        let roundIndex = (rounds.start + gameId).saturating_sub(1);
         let mut row = grid.row();
         let mut rowWidth = genpdf::Mm::from(0.0);
 
@@ -599,30 +2331,38 @@ fn exportPDF(path: PathBuf,
                 _ => {
                     match tableId {
                         0 => {
-                            let str = &format!("{}", gameId);
+                            let str = &format!("{}", roundIndex + 1);
                             let mut text = genpdf::elements::Paragraph::new(str);
                             text.set_alignment(genpdf::Alignment::Center);
                             rowWidth += style.str_width(doc.font_cache(), str) + (2 * padding).into();
                             row.push_element(text.padded(padding));
                         }
                         _ => {
-                            let team = cardData[gameId - 1].0[tableId - 1];
+                            let Some(table) = cardData.rounds[roundIndex].tables.get(tableId - 1) else {
+                                for _ in 0..4 {
+                                    row.push_element(genpdf::elements::Paragraph::new(""));
+                                }
+                                continue;
+                            };
+
+                            let marks = if keepTables { moveMarks(cardData, roundIndex, playerCount) } else { Vec::new() };
+                            let (left, middle, right) = formatTable(table, separator.clone(), displayNames, playerNames.clone(), teamNames.clone(), showSeats, &marks);
 
                             row.push_element(genpdf::elements::Paragraph::new(""));
 
-                            let str = &formatPlayersTuple(team.0, separator.clone(), displayNames, playerNames.clone());
+                            let str = &left;
                             let mut text = genpdf::elements::Paragraph::new(str);
                             text.set_alignment(genpdf::Alignment::Center);
                             rowWidth += style.str_width(doc.font_cache(), str) + (2 * padding).into();
                             row.push_element(text.padded(padding));
 
-                            let str = "vs";
+                            let str = &middle;
                             let mut text = genpdf::elements::Paragraph::new(str);
                             text.set_alignment(genpdf::Alignment::Center);
                             rowWidth += style.str_width(doc.font_cache(), str) + (2 * padding).into();
                             row.push_element(text.styled(style.italic()).padded(padding));
                             
-                            let str = &formatPlayersTuple(team.1, separator.clone(), displayNames, playerNames.clone());
+                            let str = &right;
                             let mut text = genpdf::elements::Paragraph::new(str);
                             text.set_alignment(genpdf::Alignment::Center);
                             rowWidth += style.str_width(doc.font_cache(), str) + (2 * padding).into();
@@ -648,7 +2388,7 @@ fn exportPDF(path: PathBuf,
                 _ => {
                     row.push_element(genpdf::elements::Paragraph::new(""));
 
-                    let str = &formatPlayersVector(cardData[gameId - 1].1.clone(), separator.clone(), displayNames, playerNames.clone());
+                    let str = &formatTeam(&Team { players: cardData.rounds[roundIndex].out.clone() }, separator.clone(), displayNames, playerNames.clone(), teamNames.clone());
                     let mut text = genpdf::elements::Paragraph::new(str);
                     text.set_alignment(genpdf::Alignment::Center);
                     rowWidth += style.str_width(doc.font_cache(), str) + (2 * padding).into();
@@ -669,35 +2409,190 @@ fn exportPDF(path: PathBuf,
     paper_size.width *= widthFactor;
     paper_size.height += (2 * margin).into();
     paper_size.height *= heightFactor;
+    let mut notes = Vec::new();
+
+    if showSeats {
+        notes.push(String::from("N/S partner E/W, * deals first"));
+    }
+
+    if keepTables {
+        notes.push(String::from("→ moves, • stays"));
+    }
+
+    notes.push(format!("Seed {}", cardData.seed));
+    let str = &notes.join("    ");
+    let mut text = genpdf::elements::Paragraph::new(str);
+    text.set_alignment(genpdf::Alignment::Right);
+    paper_size.height += style.line_height(doc.font_cache()) + (2 * padding).into();
+
     doc.set_paper_size(paper_size);
     doc.push(grid.framed());
+    doc.push(text.styled(style.italic()).padded(padding));
     doc.render_to_file(path).expect("Failed to write PDF file");
 }
 
-fn iWrap(index: isize, size: usize) -> usize {
+/// Winners of an award by name, partners joined with "&" and separate winners on their own
+/// lines.
+fn awardWinners(award: &Award, playerNames: &[String]) -> String {
+    award.winners.iter()
+        .map(|players| formatPlayersVector(players.clone(), String::from(" & "), true, playerNames.to_vec()))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
 
-    let mut result = index;
+/// Prints a certificate page for every award that has a winner, under the name the user gave
+/// the category.
+fn exportAwardsPDF(path: PathBuf, given: &[Award], awardNames: &[String], playerNames: Vec<String>, seed: u64) {
 
-    let iSize = size.try_into().unwrap();
+    let font_family = genpdf::fonts::from_files("./src/fonts", "LiberationSans", None)
+        .expect("Failed to load font family for pdf export");
 
-    while result >= iSize {
-        result -= iSize;
-    }
+    let mut doc = genpdf::Document::new(font_family);
+    doc.set_title("Euchre Party Rotator Awards");
+
+    let mut decorator = genpdf::SimplePageDecorator::new();
+    decorator.set_margins(25);
+    doc.set_page_decorator(decorator);
+
+    // US letter, landscape.
+    doc.set_paper_size(genpdf::Size::new(279.4, 215.9));
+
+    let style = genpdf::style::Style::new();
+    let mut first = true;
+
+    for (award, name) in given.iter().zip(awardNames).filter(|(award, _)| !award.winners.is_empty()) {
+
+        if !first {
+            doc.push(genpdf::elements::PageBreak::new());
+        }
+        first = false;
+
+        let name = if name.trim().is_empty() { award.kind.defaultName() } else { name.trim() };
+
+        let mut page = genpdf::elements::LinearLayout::vertical();
+        page.push(genpdf::elements::Break::new(3.0));
+        page.push(genpdf::elements::Paragraph::new("Certificate of Achievement").aligned(genpdf::Alignment::Center).styled(style.italic().with_font_size(18)));
+        page.push(genpdf::elements::Break::new(2.0));
+        page.push(genpdf::elements::Paragraph::new(name).aligned(genpdf::Alignment::Center).styled(style.bold().with_font_size(40)));
+        page.push(genpdf::elements::Break::new(2.0));
+        page.push(genpdf::elements::Paragraph::new("presented to").aligned(genpdf::Alignment::Center).styled(style.italic().with_font_size(14)));
+        page.push(genpdf::elements::Break::new(1.0));
+
+        for players in &award.winners {
+            let winner = formatPlayersVector(players.clone(), String::from(" & "), true, playerNames.clone());
+            page.push(genpdf::elements::Paragraph::new(winner).aligned(genpdf::Alignment::Center).styled(style.bold().with_font_size(28)));
+        }
 
-    while result < 0 {
-        result += iSize;
+        page.push(genpdf::elements::Break::new(2.0));
+        page.push(genpdf::elements::Paragraph::new(format!("{}: {}", award.kind.description(), award.detail)).aligned(genpdf::Alignment::Center).styled(style.with_font_size(14)));
+        page.push(genpdf::elements::Break::new(2.0));
+        page.push(genpdf::elements::Paragraph::new(format!("Seed {}", seed)).aligned(genpdf::Alignment::Right).styled(style.italic().with_font_size(9)));
+
+        doc.push(page.padded(5).framed());
     }
 
-    result.try_into().unwrap()
+    doc.render_to_file(path).expect("Failed to write PDF file");
 }
 
-fn uWrap(index: usize, size: usize) -> usize {
+/// Prints the bracket with a column per stage, each match lined up between the two it comes
+/// from. Matches still to be played get a blank to write the pair in.
+fn exportBracketPDF(path: PathBuf, bracket: &Bracket, separator: String, displayNames: bool, playerNames: Vec<String>, seed: u64) -> Result<(), genpdf::error::Error> {
+
+    let font_family = genpdf::fonts::from_files("./src/fonts", "LiberationSans", None)?;
+
+    let mut doc = genpdf::Document::new(font_family);
+    doc.set_title("Euchre Party Rotator Playoff");
+
+    let margin = 15;
+
+    let mut decorator = genpdf::SimplePageDecorator::new();
+    decorator.set_margins(margin);
+    doc.set_page_decorator(decorator);
+
+    let padding = 1;
+    let style = genpdf::style::Style::new();
+    let stageCount = bracket.stageCount();
+    let slots = 1 << stageCount;
+
+    let mut grid = genpdf::elements::TableLayout::new(vec![1; stageCount + 1]);
+    let mut paper_size: genpdf::Size = genpdf::Size::new(10, 10);
+    let mut widest = genpdf::Mm::from(0.0);
+
+    let label = |entrant: Entrant, score: Option<&String>| match entrant {
+        Entrant::Pair(pair) => {
+            let names = formatPlayersVector(bracket.pairs[pair].to_vec(), separator.clone(), displayNames, playerNames.clone());
+            match score.map(|points| points.trim()).filter(|points| !points.is_empty()) {
+                Some(points) => format!("{}  ({})", names, points),
+                None => names,
+            }
+        }
+        Entrant::Bye => String::from("Bye"),
+        Entrant::Undecided => String::from("________________"),
+    };
+
+    // Each stage's entrants sit halfway down the block of first-stage rows they came through.
+    let mut cells = vec![vec![String::new(); stageCount + 1]; slots + 1];
+
+    for (stage, header) in cells[0].iter_mut().take(stageCount).enumerate() {
+        *header = bracket.stageName(stage);
+    }
+
+    for stage in 0..stageCount {
+        for (index, sides) in bracket.matches(stage).into_iter().enumerate() {
+            for (side, entrant) in sides.into_iter().enumerate() {
+
+                let score = bracket.scores.get(stage).and_then(|matches| matches.get(index)).and_then(|score| score.points.get(side));
+                let block = 1 << stage;
+                let row = &mut cells[1 + (2 * index + side) * block + block / 2];
+                row[stage] = label(entrant, score);
+            }
+        }
+    }
+
+    cells[0][stageCount] = String::from("Champions");
+    cells[1 + slots / 2][stageCount] = bracket.champion().map_or_else(|| label(Entrant::Undecided, None), |pair| label(Entrant::Pair(pair), None));
+
+    for (rowIndex, rowCells) in cells.iter().enumerate() {
+
+        let mut row = grid.row();
+
+        for cell in rowCells {
+
+            let mut text = genpdf::elements::Paragraph::new(cell.as_str());
+            text.set_alignment(genpdf::Alignment::Center);
+            let width = style.str_width(doc.font_cache(), cell) + (2 * padding).into();
+
+            if width > widest {
+                widest = width;
+            }
 
-    let mut result = index;
+            match rowIndex {
+                0 => row.push_element(text.styled(style.bold()).padded(padding)),
+                _ => row.push_element(text.padded(padding)),
+            }
+        }
 
-    while result >= size {
-        result -= size;
+        paper_size.height += style.line_height(doc.font_cache()) + (2 * padding).into();
+        row.push()?;
     }
 
-    result
-}
\ No newline at end of file
+    paper_size.width = widest;
+    paper_size.width *= (stageCount + 1) as f64;
+    paper_size.width += (2 * margin).into();
+    paper_size.height += (2 * margin).into();
+
+    let mut text = genpdf::elements::Paragraph::new(format!("Seed {}", seed));
+    text.set_alignment(genpdf::Alignment::Right);
+    paper_size.height += style.line_height(doc.font_cache()) + (2 * padding).into();
+
+    doc.set_paper_size(paper_size);
+    doc.push(grid);
+    doc.push(text.styled(style.italic()).padded(padding));
+    doc.render_to_file(path)
+}
+
+/// Picks a fresh seed that is short enough to read off a printed card and type back in.
+fn newSeed() -> u64 {
+
+    rand::thread_rng().gen_range(0..1_000_000)
+}
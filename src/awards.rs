@@ -0,0 +1,258 @@
+//! End-of-night awards worked out from the card and its results.
+#![allow(non_snake_case)]
+use crate::schedule::{PlayerId, Schedule};
+use crate::scoring::{self, Scores};
+use std::collections::HashMap;
+
+/// A category of award.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AwardKind {
+    MostLoners,
+    MostEuchred,
+    BestPartners,
+    TopRound,
+    IronPlayer,
+}
+
+impl AwardKind {
+    pub const ALL: [AwardKind; 5] = [AwardKind::MostLoners, AwardKind::MostEuchred, AwardKind::BestPartners, AwardKind::TopRound, AwardKind::IronPlayer];
+
+    /// The category's name until the user gives it another.
+    pub fn defaultName(&self) -> &'static str {
+        match self {
+            AwardKind::MostLoners => "Lone Wolf",
+            AwardKind::MostEuchred => "Most Euchred",
+            AwardKind::BestPartners => "Best Partners",
+            AwardKind::TopRound => "Highest-Scoring Round",
+            AwardKind::IronPlayer => "Iron Player",
+        }
+    }
+
+    /// What the category is given for.
+    pub fn description(&self) -> &'static str {
+        match self {
+            AwardKind::MostLoners => "Most loner marches",
+            AwardKind::MostEuchred => "Most euchres suffered",
+            AwardKind::BestPartners => "Best win rate as partners",
+            AwardKind::TopRound => "Most points by a team in one round",
+            AwardKind::IronPlayer => "Played every round without sitting out",
+        }
+    }
+}
+
+/// Who won a category. Each entry in `winners` is one winner: a single player, or the partners
+/// who won it together. Ties share the award, and nobody wins a category without results.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Award {
+    pub kind: AwardKind,
+    pub winners: Vec<Vec<PlayerId>>,
+    pub detail: String,
+}
+
+/// Every award for players 1 to `playerCount`, in the order of `AwardKind::ALL`. Hands count
+/// toward games of `target` points, and `absent` players can't be iron players.
+pub fn awards(schedule: &Schedule, scores: &Scores, playerCount: usize, target: u32, absent: &[PlayerId]) -> Vec<Award> {
+
+    let hands = scoring::handStats(schedule, scores, playerCount, target);
+
+    let mostOf = |kind: AwardKind, count: &dyn Fn(&scoring::HandStats) -> usize, noun: &str| {
+
+        let best = hands.iter().map(count).max().unwrap_or(0);
+
+        Award {
+            kind,
+            winners: (1..=playerCount).filter(|&player| best > 0 && count(&hands[player - 1]) == best).map(|player| vec![player]).collect(),
+            detail: format!("{}: {}", noun, best),
+        }
+    };
+
+    vec![
+        mostOf(AwardKind::MostLoners, &|stats| stats.loners, "Loners"),
+        mostOf(AwardKind::MostEuchred, &|stats| stats.euchred, "Times euchred"),
+        bestPartners(schedule, scores),
+        topRound(schedule, scores),
+        ironPlayers(schedule, playerCount, absent),
+    ]
+}
+
+/// Games a pair has to play together before their win rate counts for best partners, so one
+/// lucky game doesn't beat a night of steady wins.
+const BEST_PARTNER_GAMES: usize = 2;
+
+/// Partners with the best record together, the one with more games between equal rates.
+fn bestPartners(schedule: &Schedule, scores: &Scores) -> Award {
+
+    let mut records: HashMap<Vec<PlayerId>, (usize, usize)> = HashMap::new();
+
+    for (roundIndex, round) in schedule.rounds.iter().enumerate() {
+        for (tableIndex, table) in round.tables.iter().enumerate().filter(|(_, table)| table.isPartnership()) {
+
+            let Some(Ok(winner)) = scores.table(roundIndex, tableIndex).map(|score| score.winner()) else { continue };
+
+            for (teamIndex, team) in table.teams.iter().enumerate() {
+
+                let mut pair = team.players.clone();
+                pair.sort_unstable();
+
+                let record = records.entry(pair).or_insert((0, 0));
+                record.0 += (teamIndex == winner) as usize;
+                record.1 += 1;
+            }
+        }
+    }
+
+    records.retain(|_, &mut (_, games)| games >= BEST_PARTNER_GAMES);
+
+    // Compared as wins × other games so equal rates tie exactly.
+    let better = |(wins, games): (usize, usize), (otherWins, otherGames): (usize, usize)| (wins * otherGames, games).cmp(&(otherWins * games, otherGames));
+    let best = records.values().copied().max_by(|&a, &b| better(a, b));
+
+    let mut winners: Vec<Vec<PlayerId>> = records.iter()
+        .filter(|(_, &record)| best.map_or(false, |best| best.0 > 0 && better(record, best).is_eq()))
+        .map(|(pair, _)| pair.clone())
+        .collect();
+    winners.sort();
+
+    Award {
+        kind: AwardKind::BestPartners,
+        winners,
+        detail: best.map_or_else(String::new, |(wins, games)| format!("{} of {} won together", wins, games)),
+    }
+}
+
+/// The team that put up the most points at one table.
+fn topRound(schedule: &Schedule, scores: &Scores) -> Award {
+
+    let mut best = 0;
+    let mut winners: Vec<(usize, Vec<PlayerId>)> = Vec::new();
+
+    for (roundIndex, round) in schedule.rounds.iter().enumerate() {
+        for (tableIndex, table) in round.tables.iter().enumerate() {
+
+            let Some(Ok(Some(points))) = scores.table(roundIndex, tableIndex).map(|score| score.parse()) else { continue };
+
+            for (team, &teamPoints) in table.teams.iter().zip(&points) {
+
+                if teamPoints > best {
+                    best = teamPoints;
+                    winners.clear();
+                }
+
+                if teamPoints == best && best > 0 {
+                    winners.push((roundIndex, team.players.clone()));
+                }
+            }
+        }
+    }
+
+    let rounds: Vec<String> = winners.iter().map(|(roundIndex, _)| (roundIndex + 1).to_string()).collect();
+
+    Award {
+        kind: AwardKind::TopRound,
+        detail: format!("{} points in round {}", best, rounds.join(", ")),
+        winners: winners.into_iter().map(|(_, players)| players).collect(),
+    }
+}
+
+/// Everyone seated in every round of the card.
+fn ironPlayers(schedule: &Schedule, playerCount: usize, absent: &[PlayerId]) -> Award {
+
+    let seated: Vec<Vec<PlayerId>> = schedule.rounds.iter().map(|round| round.players()).collect();
+
+    Award {
+        kind: AwardKind::IronPlayer,
+        winners: (1..=playerCount)
+            .filter(|player| !absent.contains(player) && !seated.is_empty() && seated.iter().all(|players| players.contains(player)))
+            .map(|player| vec![player])
+            .collect(),
+        detail: format!("Seated for all {} rounds", schedule.rounds.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::{Round, Table, Team};
+    use crate::scoring::{Hand, HandResult, TableScore};
+
+    fn table(a: PlayerId, b: PlayerId, c: PlayerId, d: PlayerId) -> Table {
+        Table { teams: vec![Team { players: vec![a, b] }, Team { players: vec![c, d] }], dealer: 0 }
+    }
+
+    /// Eight players over three rounds, where the first two rounds keep the same partners.
+    fn schedule() -> Schedule {
+        let round = |tables: Vec<Table>| Round { tables, out: Vec::new() };
+
+        Schedule {
+            seed: 0,
+            rounds: vec![
+                round(vec![table(1, 2, 3, 4), table(5, 6, 7, 8)]),
+                round(vec![table(1, 2, 5, 6), table(3, 4, 7, 8)]),
+                round(vec![table(1, 3, 5, 7), table(2, 4, 6, 8)]),
+            ],
+        }
+    }
+
+    fn results(points: [[[&str; 2]; 2]; 3]) -> Scores {
+        Scores {
+            rounds: points.iter()
+                .map(|round| round.iter().map(|table| TableScore { points: table.iter().map(|&points| points.to_owned()).collect(), ..Default::default() }).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn mostLonersAndEuchredShareTies() {
+
+        let mut scores = results([[["", ""], ["", ""]], [["", ""], ["", ""]], [["", ""], ["", ""]]]);
+        scores.rounds[0][0].hands = vec![
+            Hand { caller: 1, result: HandResult::Loner },
+            Hand { caller: 3, result: HandResult::Loner },
+            Hand { caller: 2, result: HandResult::Euchred },
+        ];
+
+        let given = awards(&schedule(), &scores, 8, 10, &[]);
+
+        assert_eq!(given[0].winners, vec![vec![1], vec![3]]);
+        assert_eq!(given[1].winners, vec![vec![2]]);
+    }
+
+    #[test]
+    fn bestPartnersNeedTwoGamesTogether() {
+
+        // Pairs 1 & 3 and 2 & 4 win their only game, while the pairs who played twice won one each.
+        let scores = results([[["10", "4"], ["4", "10"]], [["4", "10"], ["10", "4"]], [["10", "3"], ["10", "2"]]]);
+        let award = bestPartners(&schedule(), &scores);
+
+        assert_eq!(award.winners, vec![vec![1, 2], vec![3, 4], vec![5, 6], vec![7, 8]]);
+        assert_eq!(award.detail, "1 of 2 won together");
+
+        let once = results([[["", ""], ["", ""]], [["", ""], ["", ""]], [["10", "3"], ["10", "2"]]]);
+        assert!(bestPartners(&schedule(), &once).winners.is_empty());
+    }
+
+    #[test]
+    fn topRoundSharesTies() {
+
+        let scores = results([[["11", "4"], ["6", "10"]], [["8", "10"], ["3", "11"]], [["10", "3"], ["10", "2"]]]);
+        let award = topRound(&schedule(), &scores);
+
+        assert_eq!(award.winners, vec![vec![1, 2], vec![7, 8]]);
+        assert_eq!(award.detail, "11 points in round 1, 2");
+    }
+
+    #[test]
+    fn ironPlayersLeaveOutDepartedAndBenchedPlayers() {
+
+        // Player 9 never gets a seat and player 8 has gone home.
+        let award = ironPlayers(&schedule(), 9, &[8]);
+        assert_eq!(award.winners, (1..=7).map(|player| vec![player]).collect::<Vec<_>>());
+
+        let mut benched = schedule();
+        benched.rounds[1].tables[1] = table(3, 4, 7, 9);
+        benched.rounds[1].out = vec![8];
+        assert!(!ironPlayers(&benched, 9, &[]).winners.contains(&vec![8]));
+
+        assert!(ironPlayers(&Schedule::default(), 8, &[]).winners.is_empty());
+    }
+}
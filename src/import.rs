@@ -0,0 +1,234 @@
+//! Player lists pasted in or read from a CSV or TSV file.
+#![allow(non_snake_case)]
+use crate::roster::TIERS;
+use crate::schedule::{Constraint, ConstraintKind};
+use std::fmt;
+
+/// Columns read when the list has no header row, in order. Only the name is required.
+pub const COLUMNS: [&str; 5] = ["Name", "Tier", "Partner", "Never Partners", "Never Opponents"];
+
+/// What can be wrong with one row of a list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImportProblem {
+    BlankName,
+    /// The same name as the row at this index.
+    Duplicate(usize),
+    BadTier(String),
+    /// A constraint naming someone who isn't on the list.
+    UnknownName(String),
+}
+
+impl fmt::Display for ImportProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BlankName => write!(f, "No name"),
+            Self::Duplicate(row) => write!(f, "Same name as row {}", row + 1),
+            Self::BadTier(tier) => write!(f, "Tier \"{}\" isn't {} to {}", tier, TIERS.start(), TIERS.end()),
+            Self::UnknownName(name) => write!(f, "\"{}\" isn't on the list", name),
+        }
+    }
+}
+
+/// One player read from the list.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportedPlayer {
+    pub name: String,
+    pub tier: Option<u8>,
+    /// Names from the constraint columns, with the kind of constraint each one is.
+    pub links: Vec<(ConstraintKind, String)>,
+    pub problems: Vec<ImportProblem>,
+}
+
+/// Reads a list with one player per line. Fields are split on tabs when there are any and on
+/// commas otherwise, and a field may be quoted to hold the separator. A first row naming a
+/// `COLUMNS` entry is taken as the header and can put the columns in any order; several names
+/// in one constraint cell are separated with semicolons. Empty lines are skipped.
+pub fn parse(text: &str) -> Vec<ImportedPlayer> {
+
+    let separator = if text.contains('\t') { '\t' } else { ',' };
+    let mut rows = text.lines().map(|line| split(line, separator)).filter(|fields| fields.iter().any(|field| !field.is_empty())).peekable();
+
+    let isHeader = |fields: &Vec<String>| fields.iter().any(|field| column(field) == Some(0));
+    let order: Vec<Option<usize>> = match rows.peek() {
+        Some(fields) if isHeader(fields) => rows.next().unwrap_or_default().iter().map(|field| column(field)).collect(),
+        _ => (0..COLUMNS.len()).map(Some).collect(),
+    };
+
+    let kinds = [ConstraintKind::AlwaysPartners, ConstraintKind::NeverPartners, ConstraintKind::NeverOpponents];
+    let mut players: Vec<ImportedPlayer> = Vec::new();
+
+    for fields in rows {
+
+        let mut player = ImportedPlayer::default();
+
+        for (field, &column) in fields.iter().zip(&order) {
+            match column {
+                Some(0) => player.name = field.clone(),
+                Some(1) if !field.is_empty() => match field.parse() {
+                    Ok(tier) if TIERS.contains(&tier) => player.tier = Some(tier),
+                    _ => player.problems.push(ImportProblem::BadTier(field.clone())),
+                },
+                Some(column) if column >= 2 => {
+                    let names = field.split(';').map(str::trim).filter(|name| !name.is_empty());
+                    player.links.extend(names.map(|name| (kinds[column - 2], name.to_owned())));
+                }
+                _ => {}
+            }
+        }
+
+        if player.name.is_empty() {
+            player.problems.push(ImportProblem::BlankName);
+        } else if let Some(first) = players.iter().position(|other| same(&other.name, &player.name)) {
+            player.problems.push(ImportProblem::Duplicate(first));
+        }
+
+        players.push(player);
+    }
+
+    for index in 0..players.len() {
+        let unknown: Vec<ImportProblem> = players[index].links.iter()
+            .filter(|(_, name)| !players.iter().any(|player| same(&player.name, name)))
+            .map(|(_, name)| ImportProblem::UnknownName(name.clone()))
+            .collect();
+        players[index].problems.extend(unknown);
+    }
+
+    players
+}
+
+/// The constraints the list asks for, numbered by row, each pair once.
+pub fn constraints(players: &[ImportedPlayer]) -> Vec<Constraint> {
+
+    let id = |name: &str| players.iter().position(|player| same(&player.name, name)).map(|index| index + 1);
+    let mut constraints: Vec<Constraint> = Vec::new();
+
+    for (index, player) in players.iter().enumerate() {
+        for (kind, name) in &player.links {
+
+            let Some(other) = id(name) else { continue };
+            let (a, b) = ((index + 1).min(other), (index + 1).max(other));
+
+            if a != b && !constraints.iter().any(|constraint| constraint.kind == *kind && constraint.a == a && constraint.b == b) {
+                constraints.push(Constraint { kind: *kind, a, b });
+            }
+        }
+    }
+
+    constraints
+}
+
+/// The index in `COLUMNS` of a header cell, matched loosely.
+fn column(header: &str) -> Option<usize> {
+
+    let header = header.to_lowercase().replace(['-', '_'], " ");
+
+    match header.trim() {
+        "name" | "player" => Some(0),
+        "tier" | "skill" => Some(1),
+        "partner" | "always partners" => Some(2),
+        "never partners" | "avoid" => Some(3),
+        "never opponents" | "not against" => Some(4),
+        _ => None,
+    }
+}
+
+/// Names compare without case or surrounding spaces.
+fn same(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+/// The trimmed fields of one line. Quotes let a field hold the separator, and a doubled quote
+/// inside them stands for a quote.
+fn split(line: &str, separator: char) -> Vec<String> {
+
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => fields.push(std::mem::take(&mut field).trim().to_owned()),
+            c => field.push(c),
+        }
+    }
+
+    fields.push(field.trim().to_owned());
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(players: &[ImportedPlayer]) -> Vec<&str> {
+        players.iter().map(|player| player.name.as_str()).collect()
+    }
+
+    #[test]
+    fn parseReadsQuotedCsv() {
+
+        let players = parse("\"Smith, Ann\",2\n\"Bob \"\"Ace\"\" Jones\", ,\"Smith, Ann\"\n");
+
+        assert_eq!(names(&players), ["Smith, Ann", "Bob \"Ace\" Jones"]);
+        assert_eq!(players[0].tier, Some(2));
+        assert_eq!(players[1].tier, None);
+        assert_eq!(players[1].links, vec![(ConstraintKind::AlwaysPartners, String::from("Smith, Ann"))]);
+        assert!(players.iter().all(|player| player.problems.is_empty()));
+    }
+
+    #[test]
+    fn parseSplitsOnTabsWhenThereAreAny() {
+
+        let players = parse("Ann, the elder\t1\nBob\t\t\tAnn, the elder; Cy\n");
+
+        assert_eq!(names(&players), ["Ann, the elder", "Bob"]);
+        assert_eq!(players[1].links, vec![
+            (ConstraintKind::NeverPartners, String::from("Ann, the elder")),
+            (ConstraintKind::NeverPartners, String::from("Cy")),
+        ]);
+        assert_eq!(players[1].problems, vec![ImportProblem::UnknownName(String::from("Cy"))]);
+    }
+
+    #[test]
+    fn parseTakesColumnsFromTheHeader() {
+
+        let players = parse("Skill,Not-Against,Player,Notes\n3,Bob,Ann,left early\n,,Bob,\n");
+
+        assert_eq!(names(&players), ["Ann", "Bob"]);
+        assert_eq!(players[0].tier, Some(3));
+        assert_eq!(players[0].links, vec![(ConstraintKind::NeverOpponents, String::from("Bob"))]);
+
+        // Without a name column the first row is a player like any other.
+        assert_eq!(names(&parse("Tier,Partner\nAnn\n")), ["Tier", "Ann"]);
+    }
+
+    #[test]
+    fn parseFlagsBlankAndDuplicateRows() {
+
+        let players = parse("Ann,1\n\n , \n,2\nann ,6\nBob,x\n");
+
+        assert_eq!(names(&players), ["Ann", "", "ann", "Bob"]);
+        assert_eq!(players[0].problems, Vec::new());
+        assert_eq!(players[1].problems, vec![ImportProblem::BlankName]);
+        assert_eq!(players[2].problems, vec![ImportProblem::BadTier(String::from("6")), ImportProblem::Duplicate(0)]);
+        assert_eq!(players[3].problems, vec![ImportProblem::BadTier(String::from("x"))]);
+    }
+
+    #[test]
+    fn constraintsNumberPlayersByRow() {
+
+        let players = parse("Name,Partner,Never Partners\nAnn,Bob,\nBob,Ann,Cy\nCy,,Ann;Zed\n");
+
+        assert_eq!(constraints(&players), vec![
+            Constraint { kind: ConstraintKind::AlwaysPartners, a: 1, b: 2 },
+            Constraint { kind: ConstraintKind::NeverPartners, a: 2, b: 3 },
+            Constraint { kind: ConstraintKind::NeverPartners, a: 1, b: 3 },
+        ]);
+    }
+}
@@ -1,4 +1,14 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+pub mod analysis;
 mod app;
+pub mod awards;
+pub mod import;
+pub mod optimize;
+pub mod playoff;
+pub mod roster;
+pub mod schedule;
+pub mod scoring;
+pub mod timer;
+mod whist;
 pub use app::RotatorApp;
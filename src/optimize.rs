@@ -0,0 +1,523 @@
+//! Local search that polishes a card by swapping players within rounds.
+//!
+//! The optimizer scores a card with a weighted cost and runs simulated annealing over it: each
+//! step swaps two players in one round, keeping the swap when it lowers the cost and sometimes
+//! when it doesn't, so the search can climb out of local minima while the temperature is high.
+#![allow(non_snake_case)]
+use crate::analysis;
+use crate::schedule::{self, Constraint, Pin, PlayerId, Round, Schedule};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+/// How much each kind of imbalance adds to the cost of a card.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct CostWeights {
+    /// Charged for every extra time a pair partners beyond the first.
+    pub repeatPartner: f64,
+    /// Charged for every extra time a pair opposes beyond the first.
+    pub repeatOpponent: f64,
+    /// Charged for the spread of sit-out counts across players.
+    pub unevenSitOut: f64,
+    /// Charged every time a player changes table number between back-to-back rounds. Off by
+    /// default; the best card also has its tables renumbered to keep players in place when set.
+    pub tableMove: f64,
+}
+
+impl Default for CostWeights {
+    fn default() -> Self {
+        Self {
+            repeatPartner: 10.0,
+            repeatOpponent: 2.0,
+            unevenSitOut: 5.0,
+            tableMove: 0.0,
+        }
+    }
+}
+
+/// Weighted cost of `schedule`, where lower is better and 0 means nobody ever repeats. Players in
+/// `absent` only count as sitting out where the card lists them as out.
+pub fn cost(schedule: &Schedule, playerCount: usize, absent: &[PlayerId], weights: &CostWeights) -> f64 {
+    Tally::new(schedule, playerCount, absent).cost(weights)
+}
+
+/// Running pair and sit-out counts for a card, kept up to date as players are swapped.
+struct Tally {
+    playerCount: usize,
+    partners: Vec<usize>,
+    opponents: Vec<usize>,
+    outs: Vec<usize>,
+    outPairs: Vec<usize>,
+    // Repeats are counted as the number of extra meetings, summed over every meeting so that a
+    // pair meeting three times costs more than two pairs meeting twice.
+    partnerRepeats: usize,
+    opponentRepeats: usize,
+    outSquares: usize,
+    outTotal: usize,
+    /// Extra times pairs sit out together, which swaps are never allowed to raise.
+    outRepeats: usize,
+    moves: usize,
+}
+
+impl Tally {
+    fn new(schedule: &Schedule, playerCount: usize, absent: &[PlayerId]) -> Self {
+
+        let mut tally = Self {
+            playerCount,
+            partners: vec![0; (playerCount + 1) * (playerCount + 1)],
+            opponents: vec![0; (playerCount + 1) * (playerCount + 1)],
+            outs: vec![0; playerCount + 1],
+            outPairs: vec![0; (playerCount + 1) * (playerCount + 1)],
+            partnerRepeats: 0,
+            opponentRepeats: 0,
+            outSquares: 0,
+            outTotal: 0,
+            outRepeats: 0,
+            moves: 0,
+        };
+
+        for round in &schedule.rounds {
+            for tableIndex in 0..round.tables.len() {
+                tally.table(round, tableIndex, true);
+            }
+
+            // Anyone missing from a round, whether sitting out or not yet arrived, counts as out.
+            // Players who have left are missing from every new round, which isn't a sit-out.
+            let seated = round.players();
+            let missing = |player: &PlayerId| !seated.contains(player) && (round.out.contains(player) || !absent.contains(player));
+
+            for player in (1..=playerCount).filter(missing) {
+                tally.out(player, true);
+            }
+
+            for (index, &player) in round.out.iter().enumerate() {
+                for &other in &round.out[index + 1..] {
+                    Self::pair(&mut tally.outPairs, &mut tally.outRepeats, playerCount, player, other, true);
+                }
+            }
+        }
+
+        for roundIndex in 1..schedule.rounds.len() {
+            tally.moves += (1..=playerCount).filter(|&player| analysis::moved(schedule, roundIndex, player) == Some(true)).count();
+        }
+
+        tally
+    }
+
+    fn cost(&self, weights: &CostWeights) -> f64 {
+
+        let outMean = self.outTotal as f64 / self.playerCount as f64;
+        let outVariance = self.outSquares as f64 - self.outTotal as f64 * outMean;
+
+        weights.repeatPartner * self.partnerRepeats as f64
+            + weights.repeatOpponent * self.opponentRepeats as f64
+            + weights.unevenSitOut * outVariance
+            + weights.tableMove * self.moves as f64
+    }
+
+    fn table(&mut self, round: &Round, tableIndex: usize, add: bool) {
+
+        let teams = &round.tables[tableIndex].teams;
+
+        for (teamIndex, team) in teams.iter().enumerate() {
+            for (index, &player) in team.players.iter().enumerate() {
+
+                for &partner in &team.players[index + 1..] {
+                    Self::pair(&mut self.partners, &mut self.partnerRepeats, self.playerCount, player, partner, add);
+                }
+
+                for other in &teams[teamIndex + 1..] {
+                    for &opponent in &other.players {
+                        Self::pair(&mut self.opponents, &mut self.opponentRepeats, self.playerCount, player, opponent, add);
+                    }
+                }
+            }
+        }
+    }
+
+    fn pair(counts: &mut [usize], repeats: &mut usize, playerCount: usize, a: PlayerId, b: PlayerId, add: bool) {
+
+        let index = a.min(b) * (playerCount + 1) + a.max(b);
+
+        if add {
+            *repeats += counts[index];
+            counts[index] += 1;
+        } else {
+            counts[index] -= 1;
+            *repeats -= counts[index];
+        }
+    }
+
+    /// Adds or removes the pairs the player at `round.out[index]` makes with the others out.
+    fn outWith(&mut self, round: &Round, index: usize, add: bool) {
+        for (otherIndex, &other) in round.out.iter().enumerate() {
+            if otherIndex != index {
+                Self::pair(&mut self.outPairs, &mut self.outRepeats, self.playerCount, round.out[index], other, add);
+            }
+        }
+    }
+
+    fn out(&mut self, player: PlayerId, add: bool) {
+
+        if add {
+            self.outSquares += 2 * self.outs[player] + 1;
+            self.outs[player] += 1;
+            self.outTotal += 1;
+        } else {
+            self.outs[player] -= 1;
+            self.outSquares -= 2 * self.outs[player] + 1;
+            self.outTotal -= 1;
+        }
+    }
+}
+
+/// Where a player sits within a round: at a table's team, or in the out list.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Seat {
+    Table { table: usize, team: usize, slot: usize },
+    Out(usize),
+}
+
+const START_TEMPERATURE: f64 = 2.0;
+const END_TEMPERATURE: f64 = 0.01;
+
+/// Simulated annealing over a card, run a batch of steps at a time so a UI can keep drawing.
+pub struct Optimizer {
+    current: Schedule,
+    best: Schedule,
+    tally: Tally,
+    weights: CostWeights,
+    constraints: Vec<Constraint>,
+    frozen: usize,
+    pins: Vec<Pin>,
+    /// Whether tables are renumbered so players stay put, whatever moving is weighted at.
+    keepTables: bool,
+    /// Players each round's pins hold in place.
+    locked: Vec<Vec<PlayerId>>,
+    rng: StdRng,
+    temperature: f64,
+    cooling: f64,
+    currentCost: f64,
+    bestCost: f64,
+    iterations: usize,
+    maxIterations: usize,
+    /// Time the search stops at, in the same clock as the `now` passed to `step`.
+    deadline: f64,
+}
+
+impl Optimizer {
+    /// Starts from `schedule`, cooling over `maxIterations` steps. The swaps are drawn from the
+    /// card's own seed so the same card and settings always optimize the same way. Swaps that
+    /// would break one of `constraints` are never taken, the first `frozen` rounds are never
+    /// touched, and players held by `pins` stay where they are. With `keepTables` the best card
+    /// always has its tables renumbered to keep players in their seats. Players in `absent` have
+    /// left, so rounds they miss aren't counted as sit-outs.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(schedule: Schedule, playerCount: usize, absent: &[PlayerId], weights: CostWeights, constraints: Vec<Constraint>, frozen: usize, pins: Vec<Pin>, keepTables: bool, maxIterations: usize) -> Self {
+
+        let tally = Tally::new(&schedule, playerCount, absent);
+        let currentCost = tally.cost(&weights);
+
+        let mut locked = vec![Vec::new(); schedule.rounds.len()];
+
+        for pin in &pins {
+            if let Some(players) = locked.get_mut(pin.round()) {
+                players.extend(pin.players());
+            }
+        }
+
+        Self {
+            rng: StdRng::seed_from_u64(schedule.seed),
+            current: schedule.clone(),
+            best: schedule,
+            tally,
+            weights,
+            constraints,
+            frozen,
+            pins,
+            keepTables,
+            locked,
+            temperature: START_TEMPERATURE,
+            cooling: (END_TEMPERATURE / START_TEMPERATURE).powf(1.0 / maxIterations.max(1) as f64),
+            currentCost,
+            bestCost: currentCost,
+            iterations: 0,
+            maxIterations,
+            deadline: f64::INFINITY,
+        }
+    }
+
+    /// Stops the search once `step` is called at or after `deadline`. The clock is passed in as
+    /// `now` so the optimizer itself holds none.
+    pub fn setDeadline(&mut self, deadline: f64) {
+        self.deadline = deadline;
+    }
+
+    /// Runs up to `iterations` more swap attempts at time `now`, stopping early once the budget
+    /// is spent.
+    pub fn step(&mut self, iterations: usize, now: f64) {
+
+        for _ in 0..iterations {
+
+            if self.finished(now) {
+                return;
+            }
+
+            self.iterations += 1;
+            self.temperature *= self.cooling;
+
+            let roundIndex = self.rng.gen_range(self.frozen..self.current.rounds.len());
+            let seats = seats(&self.current.rounds[roundIndex]);
+
+            if seats.len() < 2 {
+                continue;
+            }
+
+            let a = seats[self.rng.gen_range(0..seats.len())];
+            let b = seats[self.rng.gen_range(0..seats.len())];
+
+            if !self.swappable(roundIndex, a, b) {
+                continue;
+            }
+
+            let outRepeats = self.tally.outRepeats;
+            self.swap(roundIndex, a, b);
+
+            // Constraints and pairs who already sat out together are kept outright, not weighed.
+            if self.tally.outRepeats > outRepeats || self.constraints.iter().any(|constraint| !constraint.holds(&self.current.rounds[roundIndex])) {
+                self.swap(roundIndex, a, b);
+                continue;
+            }
+
+            let newCost = self.tally.cost(&self.weights);
+            let delta = newCost - self.currentCost;
+
+            if delta <= 0.0 || self.rng.gen::<f64>() < (-delta / self.temperature).exp() {
+                self.currentCost = newCost;
+
+                if newCost < self.bestCost {
+                    self.bestCost = newCost;
+                    self.best = self.current.clone();
+                    if self.keepTables || self.weights.tableMove > 0.0 {
+                        schedule::orderTables(&mut self.best, self.frozen, &self.pins);
+                    }
+
+                    // Swaps move players between seats, so seats and dealers are handed out again.
+                    schedule::balanceSeats(&mut self.best, self.tally.playerCount, self.frozen);
+                }
+            } else {
+                self.swap(roundIndex, a, b);
+            }
+        }
+    }
+
+    pub fn best(&self) -> &Schedule {
+        &self.best
+    }
+
+    pub fn bestCost(&self) -> f64 {
+        self.bestCost
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// True once the iteration or time budget is spent or nothing is left to improve.
+    pub fn finished(&self, now: f64) -> bool {
+        self.iterations >= self.maxIterations || now >= self.deadline || self.bestCost <= 0.0 || self.frozen >= self.current.rounds.len()
+    }
+
+    /// Swaps that can't change the cost are skipped: two sit-outs, or two players on one team.
+    /// So are swaps that would move a pinned player.
+    fn swappable(&self, roundIndex: usize, a: Seat, b: Seat) -> bool {
+
+        let round = &self.current.rounds[roundIndex];
+
+        if [a, b].iter().any(|&seat| self.locked[roundIndex].contains(&player(round, seat))) {
+            return false;
+        }
+
+        match (a, b) {
+            (Seat::Out(_), Seat::Out(_)) => false,
+            (Seat::Table { table: tableA, team: teamA, .. }, Seat::Table { table: tableB, team: teamB, .. }) => tableA != tableB || teamA != teamB,
+            _ => true,
+        }
+    }
+
+    fn swap(&mut self, roundIndex: usize, a: Seat, b: Seat) {
+
+        self.touch(roundIndex, a, b, false);
+
+        let round = &self.current.rounds[roundIndex];
+        let playerA = player(round, a);
+        let playerB = player(round, b);
+        self.tally.moves -= self.movesAround(roundIndex, playerA) + self.movesAround(roundIndex, playerB);
+
+        let round = &mut self.current.rounds[roundIndex];
+        *seat(round, a) = playerB;
+        *seat(round, b) = playerA;
+
+        self.tally.moves += self.movesAround(roundIndex, playerA) + self.movesAround(roundIndex, playerB);
+        self.touch(roundIndex, a, b, true);
+    }
+
+    /// Table changes for `player` into and out of round `roundIndex`.
+    fn movesAround(&self, roundIndex: usize, player: PlayerId) -> usize {
+        [roundIndex, roundIndex + 1].iter().filter(|&&round| analysis::moved(&self.current, round, player) == Some(true)).count()
+    }
+
+    /// Adds or removes the counts of every table and sit-out slot involved in a swap.
+    fn touch(&mut self, roundIndex: usize, a: Seat, b: Seat, add: bool) {
+
+        let round = &self.current.rounds[roundIndex];
+        let mut tables: Vec<usize> = Vec::with_capacity(2);
+
+        for seat in [a, b] {
+            match seat {
+                Seat::Table { table, .. } => {
+                    if !tables.contains(&table) {
+                        tables.push(table);
+                    }
+                }
+                Seat::Out(index) => {
+                    self.tally.out(round.out[index], add);
+                    self.tally.outWith(round, index, add);
+                }
+            }
+        }
+
+        for table in tables {
+            self.tally.table(round, table, add);
+        }
+    }
+}
+
+fn seats(round: &Round) -> Vec<Seat> {
+
+    let mut seats = Vec::new();
+
+    for (table, contents) in round.tables.iter().enumerate() {
+        for (team, members) in contents.teams.iter().enumerate() {
+            for slot in 0..members.players.len() {
+                seats.push(Seat::Table { table, team, slot });
+            }
+        }
+    }
+
+    seats.extend((0..round.out.len()).map(Seat::Out));
+    seats
+}
+
+fn player(round: &Round, at: Seat) -> PlayerId {
+    match at {
+        Seat::Table { table, team, slot } => round.tables[table].teams[team].players[slot],
+        Seat::Out(index) => round.out[index],
+    }
+}
+
+fn seat(round: &mut Round, at: Seat) -> &mut PlayerId {
+    match at {
+        Seat::Table { table, team, slot } => &mut round.tables[table].teams[team].players[slot],
+        Seat::Out(index) => &mut round.out[index],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::{generate, Leftover, ScheduleParams};
+
+    fn params(playerCount: usize, gameCount: usize) -> ScheduleParams {
+        ScheduleParams { playerCount, gameCount, seed: playerCount as u64, ..Default::default() }
+    }
+
+    fn optimizer(schedule: &Schedule, playerCount: usize, frozen: usize, pins: Vec<Pin>, maxIterations: usize) -> Optimizer {
+        Optimizer::new(schedule.clone(), playerCount, &[], CostWeights::default(), Vec::new(), frozen, pins, false, maxIterations)
+    }
+
+    #[test]
+    fn optimizingNeverRaisesTheCost() {
+
+        for playerCount in [9, 11, 14, 18] {
+
+            let schedule = generate(&params(playerCount, 10)).unwrap();
+            let before = cost(&schedule, playerCount, &[], &CostWeights::default());
+
+            let mut optimizer = optimizer(&schedule, playerCount, 0, Vec::new(), 20_000);
+            optimizer.step(20_000, 0.0);
+
+            let after = cost(optimizer.best(), playerCount, &[], &CostWeights::default());
+            assert!(after <= before, "{} players: {} → {}", playerCount, before, after);
+            assert!((after - optimizer.bestCost()).abs() < 1e-9, "{} players", playerCount);
+        }
+    }
+
+    #[test]
+    fn optimizingStopsAtTheBudget() {
+
+        // Nine players over twelve rounds can't avoid repeats, so the search never runs out of
+        // things to improve.
+        let schedule = generate(&params(9, 12)).unwrap();
+
+        let mut optimizer = optimizer(&schedule, 9, 0, Vec::new(), 100);
+        optimizer.step(1_000, 0.0);
+        assert_eq!(optimizer.iterations(), 100);
+        assert!(optimizer.finished(0.0));
+
+        let mut optimizer = self::optimizer(&schedule, 9, 0, Vec::new(), 1_000);
+        optimizer.setDeadline(10.0);
+        optimizer.step(50, 0.0);
+        assert_eq!(optimizer.iterations(), 50);
+        assert!(!optimizer.finished(9.9));
+
+        optimizer.step(50, 10.0);
+        assert_eq!(optimizer.iterations(), 50);
+        assert!(optimizer.finished(10.0));
+    }
+
+    #[test]
+    fn optimizedRoundsStayValid() {
+
+        let params = ScheduleParams { leftover: Leftover::ThreeHanded, ..params(13, 6) };
+        let schedule = generate(&params).unwrap();
+
+        let pins = vec![
+            Pin::Table { round: 3, table: 0, content: schedule.rounds[3].tables[0].clone() },
+            Pin::Seat { round: 4, table: 1, player: schedule.rounds[4].tables[1].players()[0] },
+        ];
+        let params = ScheduleParams { pins: pins.clone(), ..params };
+
+        let mut optimizer = optimizer(&schedule, 13, 2, pins, 20_000);
+        optimizer.step(20_000, 0.0);
+        let best = optimizer.best();
+
+        assert_eq!(best.validate(&params), Ok(()));
+        assert_eq!(best.rounds[..2], schedule.rounds[..2]);
+
+        let shape = |round: &Round| round.tables.iter().map(|table| table.teams.iter().map(|team| team.players.len()).collect::<Vec<_>>()).collect::<Vec<_>>();
+        let everyone = |round: &Round| {
+            let mut players: Vec<PlayerId> = round.players().into_iter().chain(round.out.iter().copied()).collect();
+            players.sort_unstable();
+            players
+        };
+
+        for (before, after) in schedule.rounds.iter().zip(&best.rounds) {
+            assert_eq!(shape(after), shape(before));
+            assert_eq!(everyone(after), everyone(before));
+        }
+    }
+
+    #[test]
+    fn departedPlayersDontCountAsSittingOut() {
+
+        let first = generate(&params(12, 6)).unwrap();
+        let params = ScheduleParams { played: first.rounds[..3].to_vec(), absent: vec![5], ..params(12, 6) };
+        let schedule = generate(&params).unwrap();
+
+        let listed = schedule.rounds.iter().filter(|round| round.out.contains(&5)).count();
+
+        assert_eq!(Tally::new(&schedule, 12, &[5]).outs[5], listed);
+        assert_eq!(Tally::new(&schedule, 12, &[]).outs[5], listed + 3);
+    }
+}
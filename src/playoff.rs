@@ -0,0 +1,205 @@
+//! Single-elimination playoff between pairs drawn from the top of the standings.
+#![allow(non_snake_case)]
+use crate::schedule::PlayerId;
+use crate::scoring::TableScore;
+use std::fmt;
+
+/// A knockout bracket. Pairs are seeded so the top seeds can only meet late, and when the pair
+/// count isn't a power of two the top seeds get a bye through the first stage.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Bracket {
+    /// Pairs in seed order, best first.
+    pub pairs: Vec<[PlayerId; 2]>,
+    /// Points for the two sides of every match, by stage and then by match.
+    pub scores: Vec<Vec<TableScore>>,
+}
+
+/// Who fills one side of a match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Entrant {
+    /// The pair at this index in `Bracket::pairs`.
+    Pair(usize),
+    Bye,
+    /// Waiting on the result of an earlier match.
+    Undecided,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlayoffError {
+    NotEnoughPlayers { needed: usize, found: usize },
+    OddPlayerCount(usize),
+}
+
+impl fmt::Display for PlayoffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughPlayers { needed, found } => write!(f, "A playoff needs at least {} players, found {}", needed, found),
+            Self::OddPlayerCount(count) => write!(f, "{} players can't all be paired up", count),
+        }
+    }
+}
+
+impl std::error::Error for PlayoffError {}
+
+impl Bracket {
+    /// Takes the first `playerCount` players of `ranking` and pairs the best with the worst,
+    /// the second best with the second worst and so on, so the pairs come out close in
+    /// strength. Pairs are seeded by their better player.
+    pub fn new(ranking: &[PlayerId], playerCount: usize) -> Result<Self, PlayoffError> {
+
+        if playerCount % 2 != 0 {
+            return Err(PlayoffError::OddPlayerCount(playerCount));
+        }
+
+        if playerCount < 4 || ranking.len() < playerCount {
+            return Err(PlayoffError::NotEnoughPlayers { needed: playerCount.max(4), found: ranking.len() });
+        }
+
+        let pairs = (0..playerCount / 2).map(|seed| [ranking[seed], ranking[playerCount - 1 - seed]]).collect();
+        Ok(Self { pairs, scores: Vec::new() })
+    }
+
+    /// Stages from the first to the final.
+    pub fn stageCount(&self) -> usize {
+        self.pairs.len().next_power_of_two().trailing_zeros() as usize
+    }
+
+    /// "Final", "Semifinal" and "Quarterfinal" for the last three stages, and the stage number
+    /// before them.
+    pub fn stageName(&self, stage: usize) -> String {
+        match self.stageCount() - stage {
+            1 => String::from("Final"),
+            2 => String::from("Semifinal"),
+            3 => String::from("Quarterfinal"),
+            _ => format!("Round {}", stage + 1),
+        }
+    }
+
+    /// Both sides of every match in `stage`, top of the bracket first.
+    pub fn matches(&self, stage: usize) -> Vec<[Entrant; 2]> {
+        self.stages().into_iter().nth(stage).unwrap_or_default()
+    }
+
+    /// The pair that goes through from a match, once its score has a winner or it is a bye.
+    pub fn winner(&self, stage: usize, index: usize) -> Option<usize> {
+        self.matches(stage).get(index).and_then(|&sides| self.through(stage, index, sides))
+    }
+
+    /// Every stage's matches, each filled in from the results of the stage before.
+    fn stages(&self) -> Vec<Vec<[Entrant; 2]>> {
+
+        let entrant = |seed: usize| if seed < self.pairs.len() { Entrant::Pair(seed) } else { Entrant::Bye };
+        let mut stages: Vec<Vec<[Entrant; 2]>> = vec![seeding(self.pairs.len().next_power_of_two()).chunks(2).map(|sides| [entrant(sides[0]), entrant(sides[1])]).collect()];
+
+        for stage in 1..self.stageCount() {
+
+            let previous = &stages[stage - 1];
+            let entrant = |index: usize| self.through(stage - 1, index, previous[index]).map_or(Entrant::Undecided, Entrant::Pair);
+            let matches = (0..previous.len() / 2).map(|index| [entrant(2 * index), entrant(2 * index + 1)]).collect();
+
+            stages.push(matches);
+        }
+
+        stages
+    }
+
+    /// The pair that goes through from a match between `sides`.
+    fn through(&self, stage: usize, index: usize, sides: [Entrant; 2]) -> Option<usize> {
+        match sides {
+            [Entrant::Pair(pair), Entrant::Bye] | [Entrant::Bye, Entrant::Pair(pair)] => Some(pair),
+            [Entrant::Pair(a), Entrant::Pair(b)] => {
+                let winner = self.scores.get(stage)?.get(index)?.winner().ok()?;
+                Some([a, b][winner])
+            }
+            _ => None,
+        }
+    }
+
+    pub fn champion(&self) -> Option<usize> {
+        self.winner(self.stageCount().checked_sub(1)?, 0)
+    }
+
+    /// Gives every seat in the pairs to `label` of the player there.
+    pub fn relabel(&mut self, label: impl Fn(PlayerId) -> PlayerId) {
+        for player in self.pairs.iter_mut().flatten() {
+            *player = label(*player);
+        }
+    }
+
+    /// The score of a match, making room for it if it's the first entry.
+    pub fn scoreMut(&mut self, stage: usize, index: usize) -> &mut TableScore {
+
+        if self.scores.len() <= stage {
+            self.scores.resize(stage + 1, Vec::new());
+        }
+
+        let matches = &mut self.scores[stage];
+
+        if matches.len() <= index {
+            matches.resize(index + 1, TableScore::default());
+        }
+
+        let score = &mut matches[index];
+        score.points.resize(2, String::new());
+        score
+    }
+}
+
+/// Seed order down a bracket of `size` slots, 0-based, so that seeds 1 and 2 can only meet in
+/// the final: 1 v 8, 4 v 5, 2 v 7, 3 v 6 for eight.
+fn seeding(size: usize) -> Vec<usize> {
+
+    let mut seeds = vec![0];
+
+    while seeds.len() < size {
+        let count = seeds.len() * 2;
+        seeds = seeds.iter().flat_map(|&seed| [seed, count - 1 - seed]).collect();
+    }
+
+    seeds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newPairsBestWithWorst() {
+
+        let ranking: Vec<PlayerId> = (11..=20).collect();
+        let bracket = Bracket::new(&ranking, 8).unwrap();
+
+        assert_eq!(bracket.pairs, vec![[11, 18], [12, 17], [13, 16], [14, 15]]);
+        assert_eq!(bracket.stageCount(), 2);
+        assert_eq!(bracket.matches(0), vec![[Entrant::Pair(0), Entrant::Pair(3)], [Entrant::Pair(1), Entrant::Pair(2)]]);
+        assert_eq!(bracket.matches(1), vec![[Entrant::Undecided, Entrant::Undecided]]);
+    }
+
+    #[test]
+    fn topSeedsGetTheByes() {
+
+        let ranking: Vec<PlayerId> = (1..=12).collect();
+        let mut bracket = Bracket::new(&ranking, 12).unwrap();
+
+        assert_eq!(bracket.stageCount(), 3);
+        assert_eq!(bracket.stageName(0), "Quarterfinal");
+        assert_eq!(bracket.matches(0), vec![
+            [Entrant::Pair(0), Entrant::Bye],
+            [Entrant::Pair(3), Entrant::Pair(4)],
+            [Entrant::Pair(1), Entrant::Bye],
+            [Entrant::Pair(2), Entrant::Pair(5)],
+        ]);
+        assert_eq!(bracket.matches(1), vec![[Entrant::Pair(0), Entrant::Undecided], [Entrant::Pair(1), Entrant::Undecided]]);
+
+        bracket.scoreMut(0, 1).points = vec![String::from("4"), String::from("10")];
+        assert_eq!(bracket.matches(1)[0], [Entrant::Pair(0), Entrant::Pair(4)]);
+        assert_eq!(bracket.champion(), None);
+    }
+
+    #[test]
+    fn newNeedsAnEvenFieldOfFour() {
+        assert_eq!(Bracket::new(&[1, 2, 3, 4, 5, 6, 7], 7), Err(PlayoffError::OddPlayerCount(7)));
+        assert_eq!(Bracket::new(&[1, 2, 3, 4], 2), Err(PlayoffError::NotEnoughPlayers { needed: 4, found: 4 }));
+        assert_eq!(Bracket::new(&[1, 2, 3, 4, 5, 6, 7, 8], 10), Err(PlayoffError::NotEnoughPlayers { needed: 10, found: 8 }));
+    }
+}
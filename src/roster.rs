@@ -0,0 +1,165 @@
+//! Players known from earlier events, saved apart from the card so they outlive any one night.
+#![allow(non_snake_case)]
+use std::ops::RangeInclusive;
+
+/// Storage key the roster is saved under, next to the app's own state.
+pub const STORAGE_KEY: &str = "roster";
+
+/// Skill tiers a player can be given, 1 being the strongest.
+pub const TIERS: RangeInclusive<u8> = 1..=5;
+
+/// How many matches the name boxes offer at once.
+const SUGGESTION_COUNT: usize = 8;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct KnownPlayer {
+    pub name: String,
+    pub nickname: String,
+    pub notes: String,
+    /// One of `TIERS`, or `None` when unrated.
+    pub tier: Option<u8>,
+}
+
+impl KnownPlayer {
+    /// The name with the nickname and tier after it, for picking from a list.
+    pub fn label(&self) -> String {
+
+        let mut label = self.name.clone();
+
+        if !self.nickname.is_empty() {
+            label += &format!(" \"{}\"", self.nickname);
+        }
+
+        if let Some(tier) = self.tier {
+            label += &format!(" · Tier {}", tier);
+        }
+
+        label
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Roster {
+    pub players: Vec<KnownPlayer>,
+}
+
+impl Roster {
+    /// Known players whose name or nickname matches `text`, those starting with it first. Nothing
+    /// is offered for a blank box or one that already holds a known name.
+    pub fn suggestions(&self, text: &str) -> Vec<&KnownPlayer> {
+
+        let text = text.trim().to_lowercase();
+
+        if text.is_empty() || self.find(&text).is_some() {
+            return Vec::new();
+        }
+
+        let matching = |player: &&KnownPlayer, starts: bool| {
+            [&player.name, &player.nickname].iter().any(|field| {
+                let field = field.to_lowercase();
+                if starts { field.starts_with(&text) } else { field.contains(&text) }
+            })
+        };
+
+        let mut suggestions: Vec<&KnownPlayer> = self.players.iter().filter(|player| matching(player, true)).collect();
+        suggestions.extend(self.players.iter().filter(|player| !matching(player, true) && matching(player, false)));
+        suggestions.truncate(SUGGESTION_COUNT);
+        suggestions
+    }
+
+    /// The known player called `name`, ignoring case and surrounding spaces.
+    pub fn find(&self, name: &str) -> Option<&KnownPlayer> {
+        let name = name.trim().to_lowercase();
+        self.players.iter().find(|player| player.name.trim().to_lowercase() == name)
+    }
+
+    /// The known player called `name`, added to the roster if there isn't one.
+    pub fn entry(&mut self, name: &str) -> &mut KnownPlayer {
+
+        let lowered = name.trim().to_lowercase();
+
+        let index = match self.players.iter().position(|player| player.name.trim().to_lowercase() == lowered) {
+            Some(index) => index,
+            None => {
+                self.players.push(KnownPlayer { name: name.trim().to_owned(), ..Default::default() });
+                self.players.len() - 1
+            }
+        };
+
+        &mut self.players[index]
+    }
+
+    /// Adds every name in `names` that isn't blank or known yet, returning how many were new.
+    pub fn remember<'a>(&mut self, names: impl IntoIterator<Item = &'a String>) -> usize {
+
+        let mut added = 0;
+
+        for name in names {
+            if !name.trim().is_empty() && self.find(name).is_none() {
+                self.players.push(KnownPlayer { name: name.trim().to_owned(), ..Default::default() });
+                added += 1;
+            }
+        }
+
+        added
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roster(names: &[(&str, &str)]) -> Roster {
+        Roster {
+            players: names.iter().map(|&(name, nickname)| KnownPlayer { name: name.to_owned(), nickname: nickname.to_owned(), ..Default::default() }).collect(),
+        }
+    }
+
+    fn names<'a>(players: &[&'a KnownPlayer]) -> Vec<&'a str> {
+        players.iter().map(|player| player.name.as_str()).collect()
+    }
+
+    #[test]
+    fn suggestionsIgnoreCase() {
+
+        let roster = roster(&[("Alice", ""), ("Bob", "Bobcat"), ("Carol", "")]);
+
+        assert_eq!(names(&roster.suggestions("AL")), vec!["Alice"]);
+        assert_eq!(names(&roster.suggestions("  bOBc ")), vec!["Bob"]);
+        assert!(roster.suggestions("").is_empty());
+
+        // A name that's already known needs no suggestions.
+        assert!(roster.suggestions("carol").is_empty());
+        assert_eq!(roster.find(" CAROL ").map(|player| player.name.as_str()), Some("Carol"));
+    }
+
+    #[test]
+    fn suggestionsPutPrefixesFirst() {
+
+        let roster = roster(&[("Jan", ""), ("Anna", ""), ("Dan", "Annie"), ("Hannah", "")]);
+
+        assert_eq!(names(&roster.suggestions("an")), vec!["Anna", "Dan", "Jan", "Hannah"]);
+    }
+
+    #[test]
+    fn suggestionsStopAtTheCount() {
+
+        let named: Vec<(String, &str)> = (1..=12).map(|index| (format!("Player {}", index), "")).collect();
+        let borrowed: Vec<(&str, &str)> = named.iter().map(|(name, nickname)| (name.as_str(), *nickname)).collect();
+        let roster = roster(&borrowed);
+
+        assert_eq!(roster.suggestions("player").len(), SUGGESTION_COUNT);
+    }
+
+    #[test]
+    fn rememberSkipsBlankAndKnownNames() {
+
+        let mut roster = roster(&[("Alice", "")]);
+        let names: Vec<String> = vec!["alice".into(), "  ".into(), " Bob ".into(), "".into(), "BOB".into()];
+
+        assert_eq!(roster.remember(&names), 1);
+        assert_eq!(roster.players.iter().map(|player| player.name.as_str()).collect::<Vec<_>>(), vec!["Alice", "Bob"]);
+    }
+}
@@ -0,0 +1,2082 @@
+//! Rotation card generation, usable without starting the GUI.
+//!
+//! ```
+//! use euchre_party_rotator::schedule::{generate, ScheduleParams};
+//!
+//! let params = ScheduleParams { playerCount: 12, gameCount: 11, seed: 42, ..Default::default() };
+//! let schedule = generate(&params).unwrap();
+//! assert!(schedule.validate(&params).is_ok());
+//! ```
+#![allow(non_snake_case)]
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fmt;
+use crate::analysis;
+use crate::optimize::{CostWeights, Optimizer};
+use crate::whist;
+
+/// Players are numbered from 1, the same way they are labelled on the card.
+pub type PlayerId = usize;
+
+/// Players who partner each other at a table.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Team {
+    pub players: Vec<PlayerId>,
+}
+
+/// One table for one round, listed as the teams facing each other. Partners sit across from
+/// each other, so going clockwise from North the seats alternate between teams.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Table {
+    pub teams: Vec<Team>,
+    /// Index into `seating` of whoever deals the first hand.
+    #[serde(default)]
+    pub dealer: usize,
+}
+
+/// Every table played in a round, plus whoever sits the round out.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Round {
+    pub tables: Vec<Table>,
+    pub out: Vec<PlayerId>,
+}
+
+/// A full rotation card along with the seed that produced it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Schedule {
+    pub seed: u64,
+    pub rounds: Vec<Round>,
+}
+
+/// How many players make up a full table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum TableFormat {
+    /// Two teams of two.
+    #[default]
+    FourHanded,
+    /// Two teams of three.
+    SixHanded,
+}
+
+impl TableFormat {
+    pub const ALL: [TableFormat; 2] = [TableFormat::FourHanded, TableFormat::SixHanded];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TableFormat::FourHanded => "4-handed (2 vs 2)",
+            TableFormat::SixHanded => "6-handed (3 vs 3)",
+        }
+    }
+
+    pub fn teamSize(&self) -> usize {
+        match self {
+            TableFormat::FourHanded => 2,
+            TableFormat::SixHanded => 3,
+        }
+    }
+
+    pub fn seats(&self) -> usize {
+        2 * self.teamSize()
+    }
+}
+
+/// What to do with the players left over when the count isn't a multiple of four. Six-handed
+/// cards always sit their leftovers out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Leftover {
+    /// Leftover players sit the round out.
+    #[default]
+    SitOut,
+    /// Leftover players fill three-handed cutthroat tables, borrowing from full tables as needed.
+    ThreeHanded,
+    /// Each leftover player joins a full table, which plays five-handed with the dealer sitting out.
+    FiveHanded,
+}
+
+impl Leftover {
+    pub const ALL: [Leftover; 3] = [Leftover::SitOut, Leftover::ThreeHanded, Leftover::FiveHanded];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Leftover::SitOut => "Sit out",
+            Leftover::ThreeHanded => "3-handed tables",
+            Leftover::FiveHanded => "5-handed tables",
+        }
+    }
+}
+
+/// How players are grouped into teams over the night.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum ScheduleMode {
+    /// Partners rotate every round.
+    #[default]
+    Rotation,
+    /// Registered pairs stay together and play a round-robin against the other pairs. Pair `k`
+    /// is players `2k - 1` and `2k`.
+    FixedPairs,
+    /// Progressive euchre: only the first round is drawn, and each round after it is built from
+    /// the results of the one before by `progress`.
+    Progressive,
+    /// Swiss pairing: only the first round is drawn, and each round after it seats players of
+    /// similar standing together through `swiss`.
+    Swiss,
+}
+
+impl ScheduleMode {
+    pub const ALL: [ScheduleMode; 4] = [ScheduleMode::Rotation, ScheduleMode::FixedPairs, ScheduleMode::Progressive, ScheduleMode::Swiss];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScheduleMode::Rotation => "Rotating partners",
+            ScheduleMode::FixedPairs => "Fixed pairs",
+            ScheduleMode::Progressive => "Progressive",
+            ScheduleMode::Swiss => "Swiss",
+        }
+    }
+
+    /// True for modes whose card grows a round at a time as results come in.
+    pub fn roundByRound(&self) -> bool {
+        matches!(self, ScheduleMode::Progressive | ScheduleMode::Swiss)
+    }
+}
+
+/// A request about two players that every round has to honour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum ConstraintKind {
+    NeverPartners,
+    AlwaysPartners,
+    NeverOpponents,
+}
+
+impl ConstraintKind {
+    pub const ALL: [ConstraintKind; 3] = [ConstraintKind::NeverPartners, ConstraintKind::AlwaysPartners, ConstraintKind::NeverOpponents];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConstraintKind::NeverPartners => "Never partners",
+            ConstraintKind::AlwaysPartners => "Always partners",
+            ConstraintKind::NeverOpponents => "Never opponents",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Constraint {
+    pub kind: ConstraintKind,
+    pub a: PlayerId,
+    pub b: PlayerId,
+}
+
+impl Constraint {
+    /// Whether `round` honours the constraint. Always-partners may also sit out together, and
+    /// everyone at a three- or five-handed table counts as an opponent.
+    pub fn holds(&self, round: &Round) -> bool {
+
+        let seatA = round.teamOf(self.a);
+        let seatB = round.teamOf(self.b);
+        let partnered = seatA.is_some() && seatA == seatB && round.tables[seatA.unwrap().0].isPartnership();
+        let opposed = matches!((seatA, seatB), (Some((tableA, teamA)), Some((tableB, teamB))) if tableA == tableB && teamA != teamB);
+
+        match self.kind {
+            ConstraintKind::NeverPartners => !partnered,
+            ConstraintKind::AlwaysPartners => partnered || (seatA.is_none() && seatB.is_none()),
+            ConstraintKind::NeverOpponents => !opposed,
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} & {}", self.kind.label(), self.a, self.b)
+    }
+}
+
+/// Part of a card that regenerating has to keep. Round and table numbers are 0-based.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Pin {
+    /// A whole round exactly as given.
+    Round { round: usize, content: Round },
+    /// One table with the same teams at the same table number.
+    Table { round: usize, table: usize, content: Table },
+    /// One player somewhere at the given table.
+    Seat { round: usize, table: usize, player: PlayerId },
+}
+
+impl Pin {
+    pub fn round(&self) -> usize {
+        match self {
+            Pin::Round { round, .. } | Pin::Table { round, .. } | Pin::Seat { round, .. } => *round,
+        }
+    }
+
+    /// Everyone the pin keeps in place.
+    pub fn players(&self) -> Vec<PlayerId> {
+        match self {
+            Pin::Round { content, .. } => content.players().into_iter().chain(content.out.iter().copied()).collect(),
+            Pin::Table { content, .. } => content.players(),
+            Pin::Seat { player, .. } => vec![*player],
+        }
+    }
+
+    /// Whether `round` keeps the pin. Seat order within a team doesn't matter.
+    pub fn holds(&self, round: &Round) -> bool {
+
+        let sameTable = |a: &Table, b: &Table| {
+            let teams = |table: &Table| {
+                let mut teams: Vec<Vec<PlayerId>> = table.teams.iter().map(|team| sorted(&team.players)).collect();
+                teams.sort();
+                teams
+            };
+            teams(a) == teams(b)
+        };
+
+        match self {
+            Pin::Round { content, .. } => {
+                content.tables.len() == round.tables.len()
+                    && content.tables.iter().zip(&round.tables).all(|(a, b)| sameTable(a, b))
+                    && sorted(&content.out) == sorted(&round.out)
+            }
+            Pin::Table { table, content, .. } => round.tables.get(*table).map_or(false, |seated| sameTable(content, seated)),
+            Pin::Seat { table, player, .. } => round.teamOf(*player).map(|(seated, _)| seated) == Some(*table),
+        }
+    }
+
+    /// Renumbers the players in the pin the same way as `Round::relabel`.
+    pub fn relabel(&mut self, label: impl Fn(PlayerId) -> PlayerId) {
+        match self {
+            Pin::Round { content, .. } => content.relabel(label),
+            Pin::Table { content, .. } => {
+                for player in content.teams.iter_mut().flat_map(|team| team.players.iter_mut()) {
+                    *player = label(*player);
+                }
+            }
+            Pin::Seat { player, .. } => *player = label(*player),
+        }
+    }
+}
+
+fn sorted(players: &[PlayerId]) -> Vec<PlayerId> {
+    let mut players = players.to_vec();
+    players.sort_unstable();
+    players
+}
+
+/// Everything `generate` needs to build a card.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct ScheduleParams {
+    pub playerCount: usize,
+    pub gameCount: usize,
+    pub seed: u64,
+    #[serde(default)]
+    pub leftover: Leftover,
+    #[serde(default)]
+    pub format: TableFormat,
+    #[serde(default)]
+    pub mode: ScheduleMode,
+    #[serde(default)]
+    pub constraints: Vec<Constraint>,
+    /// Number the tables in each round so players change tables as little as possible.
+    #[serde(default)]
+    pub keepTables: bool,
+    /// Rounds already played, which open the card unchanged. Only the rounds after them are
+    /// built, steering clear of the partners and opponents already seen.
+    #[serde(default)]
+    pub played: Vec<Round>,
+    /// Players still on the roster who take no part in the rounds being built, such as those
+    /// who left early. Fixed pairs always play in full, so this only applies to rotations.
+    #[serde(default)]
+    pub absent: Vec<PlayerId>,
+    /// Rounds, tables and seats to keep while the rest of the card is rebuilt around them.
+    /// Fixed pairs keep their round-robin, so pins only apply to rotations.
+    #[serde(default)]
+    pub pins: Vec<Pin>,
+}
+
+impl ScheduleParams {
+    /// Seats at each table in a round, full tables first. When there are too few players for
+    /// the chosen leftover tables, the extras sit out instead.
+    pub fn tableSizes(&self) -> Vec<usize> {
+
+        let seats = self.tableFormat().seats();
+        let playerCount = self.activeCount();
+        let leftover = playerCount % seats;
+
+        let (size, count) = match self.leftover {
+            _ if leftover == 0 || self.tableFormat() != TableFormat::FourHanded || self.mode != ScheduleMode::Rotation => (seats, 0),
+            Leftover::SitOut => (seats, 0),
+            Leftover::ThreeHanded => (3, 4 - leftover),
+            Leftover::FiveHanded => (5, leftover),
+        };
+
+        if size * count > playerCount {
+            return vec![seats; playerCount / seats];
+        }
+
+        let mut sizes = vec![seats; (playerCount - size * count) / seats];
+        sizes.extend(std::iter::repeat(size).take(count));
+        sizes
+    }
+
+    /// The table format actually used. Fixed pairs and rounds built from results always play
+    /// two against two.
+    pub fn tableFormat(&self) -> TableFormat {
+        match self.mode {
+            ScheduleMode::Rotation => self.format,
+            ScheduleMode::FixedPairs | ScheduleMode::Progressive | ScheduleMode::Swiss => TableFormat::FourHanded,
+        }
+    }
+
+    pub fn tableCount(&self) -> usize {
+        self.tableSizes().len()
+    }
+
+    pub fn outCount(&self) -> usize {
+        self.activeCount() - self.tableSizes().iter().sum::<usize>()
+    }
+
+    /// Players taking part in the rounds being built, everyone but `absent`.
+    pub fn activeCount(&self) -> usize {
+        self.activePlayers().len()
+    }
+
+    pub fn activePlayers(&self) -> Vec<PlayerId> {
+        match self.mode {
+            ScheduleMode::Rotation | ScheduleMode::Progressive | ScheduleMode::Swiss => (1..=self.playerCount).filter(|player| !self.absent.contains(player)).collect(),
+            ScheduleMode::FixedPairs => (1..=self.playerCount).collect(),
+        }
+    }
+
+    /// Constraints that apply to the rounds being built, leaving out any naming an absent player.
+    /// Rounds built from results go wherever the results send them, so none apply there.
+    pub fn activeConstraints(&self) -> Vec<Constraint> {
+
+        if self.mode.roundByRound() {
+            return Vec::new();
+        }
+
+        let active = self.activePlayers();
+        self.constraints.iter().copied().filter(|constraint| active.contains(&constraint.a) && active.contains(&constraint.b)).collect()
+    }
+
+    /// The rounds in `played` that fit on a card of `gameCount` rounds.
+    fn playedRounds(&self) -> &[Round] {
+        &self.played[..self.played.len().min(self.gameCount)]
+    }
+
+    /// Pins on rounds still to be built. Pins on played rounds or past the end of the card,
+    /// and every pin on a fixed-pair card, are left out.
+    pub fn activePins(&self) -> Vec<Pin> {
+        match self.mode {
+            ScheduleMode::Rotation => self.pins.iter().filter(|pin| (self.playedRounds().len()..self.gameCount).contains(&pin.round())).cloned().collect(),
+            ScheduleMode::FixedPairs | ScheduleMode::Progressive | ScheduleMode::Swiss => Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScheduleError {
+    NotEnoughPlayers { needed: usize, found: usize },
+    WrongRoundCount { expected: usize, found: usize },
+    WrongTableCount { round: usize, expected: usize, found: usize },
+    MalformedTable { round: usize, table: usize },
+    UnknownPlayer { round: usize, player: PlayerId },
+    PlayerRepeated { round: usize, player: PlayerId },
+    PlayerMissing { round: usize, player: PlayerId },
+    UnpairedPlayer(usize),
+    PairSplit { round: usize, table: usize },
+    InvalidConstraint(Constraint),
+    ConflictingConstraints(Constraint, Constraint),
+    ConstraintBroken { round: usize, constraint: Constraint },
+    PinDoesNotFit { round: usize },
+    ConflictingPins { round: usize },
+    PinBroken { round: usize },
+    SitOutRepeated { round: usize, a: PlayerId, b: PlayerId },
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Rounds and tables are reported 1-based to match the card.
+        match self {
+            Self::NotEnoughPlayers { needed, found } => write!(f, "At least {} players are needed, got {}", needed, found),
+            Self::WrongRoundCount { expected, found } => write!(f, "Expected {} rounds, found {}", expected, found),
+            Self::WrongTableCount { round, expected, found } => write!(f, "Round {} should have {} tables, found {}", round + 1, expected, found),
+            Self::MalformedTable { round, table } => write!(f, "Table {} in round {} is not seated for its size", table + 1, round + 1),
+            Self::UnknownPlayer { round, player } => write!(f, "Round {} lists player {}, who is not in the event", round + 1, player),
+            Self::PlayerRepeated { round, player } => write!(f, "Player {} appears more than once in round {}", player, round + 1),
+            Self::PlayerMissing { round, player } => write!(f, "Player {} is missing from round {}", player, round + 1),
+            Self::UnpairedPlayer(count) => write!(f, "Fixed pairs need an even number of players, got {}", count),
+            Self::PairSplit { round, table } => write!(f, "Table {} in round {} splits up a registered pair", table + 1, round + 1),
+            Self::InvalidConstraint(constraint) => write!(f, "\"{}\" needs two different players from the event", constraint),
+            Self::ConflictingConstraints(first, second) => write!(f, "\"{}\" and \"{}\" can't both be kept", first, second),
+            Self::ConstraintBroken { round, constraint } => write!(f, "Round {} can't keep \"{}\" with these player and round counts", round + 1, constraint),
+            Self::PinDoesNotFit { round } => write!(f, "A pin in round {} no longer fits the players and tables", round + 1),
+            Self::ConflictingPins { round } => write!(f, "Pins in round {} ask for the same player or table twice", round + 1),
+            Self::PinBroken { round } => write!(f, "Round {} doesn't keep its pins", round + 1),
+            Self::SitOutRepeated { round, a, b } => write!(f, "Players {} and {} sit out together again in round {}, as the card is too long to keep sit-outs apart", a, b, round + 1),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+impl Schedule {
+    /// Checks that every round seats each player exactly once in the shape `params` asks for.
+    /// Rounds already played are taken as they are, and a progressive or Swiss card may stop
+    /// short of `gameCount` while later rounds wait on results.
+    pub fn validate(&self, params: &ScheduleParams) -> Result<(), ScheduleError> {
+
+        if params.activeCount() < params.tableFormat().seats() {
+            return Err(ScheduleError::NotEnoughPlayers { needed: params.tableFormat().seats(), found: params.activeCount() });
+        }
+
+        if params.mode == ScheduleMode::FixedPairs && params.playerCount % 2 != 0 {
+            return Err(ScheduleError::UnpairedPlayer(params.playerCount));
+        }
+
+        let short = params.mode.roundByRound() && (1..params.gameCount).contains(&self.rounds.len());
+
+        if self.rounds.len() != params.gameCount && !short {
+            return Err(ScheduleError::WrongRoundCount { expected: params.gameCount, found: self.rounds.len() });
+        }
+
+        let sizes = params.tableSizes();
+        let active = params.activePlayers();
+        let constraints = params.activeConstraints();
+        let pins = params.activePins();
+
+        for (roundIndex, round) in self.rounds.iter().enumerate().skip(params.playedRounds().len()) {
+
+            if round.tables.len() != sizes.len() {
+                return Err(ScheduleError::WrongTableCount { round: roundIndex, expected: sizes.len(), found: round.tables.len() });
+            }
+
+            let mut seen = vec![false; params.playerCount + 1];
+
+            for (tableIndex, (table, &size)) in round.tables.iter().zip(&sizes).enumerate() {
+                if !table.fits(size, params.tableFormat()) || table.dealer >= size {
+                    return Err(ScheduleError::MalformedTable { round: roundIndex, table: tableIndex });
+                }
+
+                if params.mode == ScheduleMode::FixedPairs && table.teams.iter().any(|team| !isPair(&team.players)) {
+                    return Err(ScheduleError::PairSplit { round: roundIndex, table: tableIndex });
+                }
+            }
+
+            for &player in round.players().iter().chain(round.out.iter()) {
+
+                if !active.contains(&player) {
+                    return Err(ScheduleError::UnknownPlayer { round: roundIndex, player });
+                }
+
+                if seen[player] {
+                    return Err(ScheduleError::PlayerRepeated { round: roundIndex, player });
+                }
+
+                seen[player] = true;
+            }
+
+            if let Some(&player) = active.iter().find(|&&player| !seen[player]) {
+                return Err(ScheduleError::PlayerMissing { round: roundIndex, player });
+            }
+
+            if let Some(&constraint) = constraints.iter().find(|constraint| !constraint.holds(round)) {
+                return Err(ScheduleError::ConstraintBroken { round: roundIndex, constraint });
+            }
+
+            if pins.iter().any(|pin| pin.round() == roundIndex && !pin.holds(round)) {
+                return Err(ScheduleError::PinBroken { round: roundIndex });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn tableCount(&self) -> usize {
+        self.rounds.iter().map(|round| round.tables.len()).max().unwrap_or(0)
+    }
+
+    pub fn outCount(&self) -> usize {
+        self.rounds.iter().map(|round| round.out.len()).max().unwrap_or(0)
+    }
+
+    /// The first time two players sit out together again in a rotation's new rounds. Cards too
+    /// long for every group sitting out to be new still come out of `generate`, so this is the
+    /// warning that goes with them.
+    pub fn repeatedSitOut(&self, params: &ScheduleParams) -> Option<ScheduleError> {
+
+        // Fixed pairs always sit out as a pair, and the other modes sit out by standings.
+        if params.mode != ScheduleMode::Rotation {
+            return None;
+        }
+
+        let mut together: HashSet<(PlayerId, PlayerId)> = HashSet::new();
+
+        for (roundIndex, round) in self.rounds.iter().enumerate() {
+            for (index, &player) in round.out.iter().enumerate() {
+                for &other in &round.out[index + 1..] {
+
+                    let (a, b) = (player.min(other), player.max(other));
+
+                    if !together.insert((a, b)) && roundIndex >= params.playedRounds().len() {
+                        return Some(ScheduleError::SitOutRepeated { round: roundIndex, a, b });
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl Round {
+    /// Everyone seated at a table this round, in table order.
+    pub fn players(&self) -> Vec<PlayerId> {
+        self.tables.iter().flat_map(|table| table.players()).collect()
+    }
+
+    /// The table and team `player` sits at, or `None` when they sit out.
+    pub fn teamOf(&self, player: PlayerId) -> Option<(usize, usize)> {
+        self.tables.iter().enumerate().find_map(|(tableIndex, table)| {
+            table.teams.iter().position(|team| team.players.contains(&player)).map(|teamIndex| (tableIndex, teamIndex))
+        })
+    }
+
+    /// Trades the seats of two players, wherever they are in the round.
+    fn swapPlayers(&mut self, a: PlayerId, b: PlayerId) {
+
+        let seats = self.tables.iter_mut()
+            .flat_map(|table| table.teams.iter_mut())
+            .flat_map(|team| team.players.iter_mut())
+            .chain(self.out.iter_mut());
+
+        for seat in seats {
+            if *seat == a {
+                *seat = b;
+            } else if *seat == b {
+                *seat = a;
+            }
+        }
+    }
+
+    /// Gives every seat, and every spot in the out list, to `label` of the player there.
+    pub fn relabel(&mut self, label: impl Fn(PlayerId) -> PlayerId) {
+
+        let seats = self.tables.iter_mut()
+            .flat_map(|table| table.teams.iter_mut())
+            .flat_map(|team| team.players.iter_mut())
+            .chain(self.out.iter_mut());
+
+        for seat in seats {
+            *seat = label(*seat);
+        }
+    }
+}
+
+impl Table {
+    pub fn players(&self) -> Vec<PlayerId> {
+        self.teams.iter().flat_map(|team| team.players.iter().copied()).collect()
+    }
+
+    /// Players in clockwise order from the first seat, taking one from each team in turn.
+    pub fn seating(&self) -> Vec<PlayerId> {
+
+        let longest = self.teams.iter().map(|team| team.players.len()).max().unwrap_or(0);
+
+        (0..longest)
+            .flat_map(|slot| self.teams.iter().filter_map(move |team| team.players.get(slot).copied()))
+            .collect()
+    }
+
+    /// Compass point for four-handed tables, otherwise the seat number counted clockwise.
+    pub fn seatName(&self, player: PlayerId) -> Option<String> {
+
+        let seating = self.seating();
+        let seat = seating.iter().position(|&seated| seated == player)?;
+
+        Some(match seating.len() {
+            4 => ["N", "E", "S", "W"][seat].to_owned(),
+            _ => (seat + 1).to_string(),
+        })
+    }
+
+    pub fn dealerPlayer(&self) -> Option<PlayerId> {
+        self.seating().get(self.dealer).copied()
+    }
+
+    /// True for a table of two partnerships, false for short- or long-handed tables where
+    /// everyone plays for themselves.
+    pub fn isPartnership(&self) -> bool {
+        self.teams.len() == 2 && self.teams.iter().all(|team| team.players.len() > 1)
+    }
+
+    /// Whether the table is laid out the way `generate` seats `size` players: two full teams
+    /// for a full table, otherwise one team per player.
+    fn fits(&self, size: usize, format: TableFormat) -> bool {
+        match size == format.seats() {
+            true => self.isPartnership() && self.teams.iter().all(|team| team.players.len() == format.teamSize()),
+            false => self.teams.len() == size && self.teams.iter().all(|team| team.players.len() == 1),
+        }
+    }
+}
+
+/// How many rounds an exact rotation covers for `params` before anyone partners the same player
+/// twice, or `None` when `generate` has to fall back to a random search.
+pub fn exactRoundCount(params: &ScheduleParams) -> Option<usize> {
+
+    if params.mode != ScheduleMode::Rotation || params.format != TableFormat::FourHanded || params.tableSizes().iter().any(|&size| size != 4) {
+        return None;
+    }
+
+    whist::cycleLength(params.playerCount)
+}
+
+/// Rounds in a full round-robin between `pairCount` pairs, counting a bye round when the count
+/// is odd.
+pub fn roundRobinLength(pairCount: usize) -> usize {
+    pairCount - 1 + pairCount % 2
+}
+
+/// True when `players` is one of the registered pairs used by `ScheduleMode::FixedPairs`.
+pub fn isPair(players: &[PlayerId]) -> bool {
+    matches!(players, [a, b] if a.min(b) % 2 == 1 && a.max(b) - a.min(b) == 1)
+}
+
+/// Builds a card for `params`. Player counts with a known whist design get an exact rotation in
+/// which nobody partners the same player twice; other counts fall back to a random search.
+/// Fixed pairs get a round-robin instead. Any rounds in `params.played` are kept as they are.
+pub fn generate(params: &ScheduleParams) -> Result<Schedule, ScheduleError> {
+
+    if params.activeCount() < params.tableFormat().seats() {
+        return Err(ScheduleError::NotEnoughPlayers { needed: params.tableFormat().seats(), found: params.activeCount() });
+    }
+
+    checkConstraints(params)?;
+
+    let played = params.playedRounds();
+
+    for (roundIndex, round) in played.iter().enumerate() {
+        if let Some(&player) = round.players().iter().chain(&round.out).find(|&&player| player == 0 || player > params.playerCount) {
+            return Err(ScheduleError::UnknownPlayer { round: roundIndex, player });
+        }
+    }
+
+    let mut seededRng = StdRng::seed_from_u64(params.seed);
+    let gameCount = params.gameCount - played.len();
+
+    if params.mode == ScheduleMode::FixedPairs {
+
+        if params.playerCount % 2 != 0 {
+            return Err(ScheduleError::UnpairedPlayer(params.playerCount));
+        }
+
+        // Pairs can't be split up to dodge a constraint, so try other orders for the circle
+        // instead, preferring one that replays as few of the played meetings as it can.
+        let mut best: Option<(usize, Vec<Round>)> = None;
+        let mut broken = None;
+
+        for _ in 0..CONSTRAINT_ATTEMPTS / 10 {
+
+            let rounds = roundRobin(params.playerCount / 2, gameCount, &mut seededRng);
+
+            if let Some((round, constraint)) = brokenConstraint(&rounds, &params.constraints) {
+                broken = Some(ScheduleError::ConstraintBroken { round: played.len() + round, constraint });
+                continue;
+            }
+
+            let repeats = repeatMeetings(played, &rounds);
+
+            if best.as_ref().map_or(true, |(bestRepeats, _)| repeats < *bestRepeats) {
+                best = Some((repeats, rounds));
+            }
+
+            if repeats == 0 {
+                break;
+            }
+        }
+
+        let rounds = match (best, broken) {
+            (Some((_, rounds)), _) => rounds,
+            (None, Some(error)) => return Err(error),
+            (None, None) => Vec::new(),
+        };
+
+        return Ok(finish(params, rounds));
+    }
+
+    let active = params.activePlayers();
+
+    // Later progressive and Swiss rounds depend on results, so only the first is drawn here.
+    if params.mode.roundByRound() {
+
+        let mut rounds = match played.is_empty() {
+            true => shuffle(active.len(), params.tableCount(), &[], 1, params.outCount(), params.seed),
+            false => Vec::new(),
+        };
+
+        for round in &mut rounds {
+            round.relabel(|player| active[player - 1]);
+        }
+
+        return Ok(finish(params, rounds));
+    }
+
+    let constraints = params.activeConstraints();
+    let pins = params.activePins();
+
+    let sizes = params.tableSizes();
+    let fullTables = sizes.iter().filter(|&&size| size == params.tableFormat().seats()).count();
+    let shortTables = &sizes[fullTables..];
+
+    // Whist designs only cover full four-handed tables, so every other card uses the search.
+    let exact = match shortTables.is_empty() && params.format == TableFormat::FourHanded {
+        true => whist::rounds(active.len(), gameCount, &mut seededRng),
+        false => None,
+    };
+
+    let mut rounds = match (exact, params.format) {
+        (Some(rounds), _) => rounds,
+        (None, TableFormat::FourHanded) => shuffle(active.len(), fullTables, shortTables, gameCount, params.outCount(), params.seed),
+        (None, format) => shuffleTeams(active.len(), format.teamSize(), fullTables, gameCount, params.outCount(), params.seed),
+    };
+
+    // The rounds above number whoever is playing from 1, so give each seat to the real player.
+    if active.len() != params.playerCount {
+        for round in &mut rounds {
+            round.relabel(|player| active[player - 1]);
+        }
+    }
+
+    for (index, round) in rounds.iter_mut().enumerate() {
+
+        let roundIndex = played.len() + index;
+        let locked = applyPins(round, roundIndex, &pins, params)?;
+
+        keepConstraints(round, &constraints, &locked, &mut seededRng)
+            .map_err(|constraint| ScheduleError::ConstraintBroken { round: roundIndex, constraint })?;
+    }
+
+    // The new rounds were built without knowing who already met or who had to be moved for a
+    // pin, so polish them before they go out.
+    if !played.is_empty() || !pins.is_empty() {
+
+        let schedule = Schedule { seed: params.seed, rounds: played.iter().cloned().chain(rounds).collect() };
+        let mut optimizer = Optimizer::new(schedule, params.playerCount, &params.absent, CostWeights::default(), constraints, played.len(), pins, params.keepTables, HISTORY_STEPS);
+
+        optimizer.step(HISTORY_STEPS, 0.0);
+        rounds = optimizer.best().rounds[played.len()..].to_vec();
+    }
+
+    Ok(finish(params, rounds))
+}
+
+/// How many optimizer steps `generate` spends fitting new rounds around the played ones.
+const HISTORY_STEPS: usize = 50_000;
+
+/// Adds the next progressive round to `schedule`, given the team that won at each table of its
+/// last round. At the head table (Table 1) the winners stay and the losers go to the foot
+/// table; at every other table the winners move up one and the losers stay. Each pair that
+/// stays splits up to partner the pair that arrives. Whoever sat out comes back in at the foot,
+/// where the losers who stayed sit out in their place, and roster changes since the last round
+/// open or close tables at the foot.
+pub fn progress(schedule: &Schedule, params: &ScheduleParams, winners: &[usize]) -> Result<Schedule, ScheduleError> {
+
+    let roundIndex = schedule.rounds.len();
+
+    let previous = match schedule.rounds.last() {
+        Some(previous) if roundIndex < params.gameCount => previous,
+        _ => return Err(ScheduleError::WrongRoundCount { expected: params.gameCount, found: roundIndex + 1 }),
+    };
+
+    if params.activeCount() < params.tableFormat().seats() {
+        return Err(ScheduleError::NotEnoughPlayers { needed: params.tableFormat().seats(), found: params.activeCount() });
+    }
+
+    if winners.len() != previous.tables.len() {
+        return Err(ScheduleError::WrongTableCount { round: roundIndex - 1, expected: previous.tables.len(), found: winners.len() });
+    }
+
+    if let Some(table) = (0..previous.tables.len()).find(|&table| !previous.tables[table].fits(4, TableFormat::FourHanded) || winners[table] > 1) {
+        return Err(ScheduleError::MalformedTable { round: roundIndex - 1, table });
+    }
+
+    let active = params.activePlayers();
+    let foot = previous.tables.len() - 1;
+    let pair = |table: usize, won: bool| &previous.tables[table].teams[if won { winners[table] } else { 1 - winners[table] }].players;
+
+    // Each table as [stay, arrive, stay, arrive], so the first two and last two are the new teams.
+    let mut seats: Vec<Vec<Option<PlayerId>>> = (0..=foot)
+        .map(|table| {
+            let (stay, arrive) = match table {
+                0 if foot == 0 => (pair(0, true), pair(0, false)),
+                0 => (pair(0, true), pair(1, true)),
+                _ if table == foot => (pair(foot, false), pair(0, false)),
+                _ => (pair(table, false), pair(table + 1, true)),
+            };
+
+            vec![stay[0], arrive[0], stay[1], arrive[1]].into_iter()
+                .map(|player| Some(player).filter(|player| active.contains(player)))
+                .collect()
+        })
+        .collect();
+
+    let mut entering: Vec<PlayerId> = previous.out.iter().copied().filter(|player| active.contains(player)).collect();
+    entering.extend(active.iter().copied().filter(|player| !previous.players().contains(player) && !previous.out.contains(player)));
+
+    while seats.len() > params.tableCount() {
+        entering.extend(seats.pop().unwrap_or_default().into_iter().flatten());
+    }
+
+    seats.resize(params.tableCount(), vec![None; 4]);
+
+    // Open seats fill from the foot up, and whoever is left over sits out.
+    let mut entering = entering.into_iter();
+
+    for seat in seats.iter_mut().rev().flatten().filter(|seat| seat.is_none()) {
+        *seat = entering.next();
+    }
+
+    let mut out: Vec<PlayerId> = entering.collect();
+    let mut stayers = (0..seats.len()).rev().flat_map(|table| [(table, 0), (table, 2), (table, 1), (table, 3)]);
+
+    for player in out.iter_mut().filter(|player| previous.out.contains(player)) {
+        if let Some((table, seat)) = stayers.by_ref().find(|&(table, seat)| seats[table][seat].map_or(false, |seated| !previous.out.contains(&seated))) {
+            *player = seats[table][seat].replace(*player).unwrap_or(*player);
+        }
+    }
+
+    let tables = seats.into_iter()
+        .map(|table| {
+            let players: Vec<PlayerId> = table.into_iter().flatten().collect();
+            Table { teams: vec![Team { players: players[..2].to_vec() }, Team { players: players[2..].to_vec() }], dealer: 0 }
+        })
+        .collect();
+
+    let mut next = schedule.clone();
+    next.rounds.push(Round { tables, out });
+    balanceSeats(&mut next, params.playerCount, roundIndex);
+
+    Ok(next)
+}
+
+/// Adds the next Swiss round to `schedule`, seating players by `ranking`, best first, so that
+/// leaders play leaders. Byes go to the lowest-ranked players who have had the fewest, which
+/// keeps everyone to one bye until all have had one. Each table takes the best player left and
+/// three of the few just below them, choosing whoever and whichever partnering repeats the
+/// fewest partners and opponents from earlier rounds, with a small charge for every place a
+/// player is moved up.
+pub fn swiss(schedule: &Schedule, params: &ScheduleParams, ranking: &[PlayerId]) -> Result<Schedule, ScheduleError> {
+
+    let roundIndex = schedule.rounds.len();
+
+    if roundIndex >= params.gameCount {
+        return Err(ScheduleError::WrongRoundCount { expected: params.gameCount, found: roundIndex + 1 });
+    }
+
+    if params.activeCount() < params.tableFormat().seats() {
+        return Err(ScheduleError::NotEnoughPlayers { needed: params.tableFormat().seats(), found: params.activeCount() });
+    }
+
+    let active = params.activePlayers();
+
+    // Anyone the ranking doesn't know yet, such as a late arrival, starts at the bottom.
+    let mut order: Vec<PlayerId> = ranking.iter().copied().filter(|player| active.contains(player)).collect();
+    let unranked: Vec<PlayerId> = active.iter().copied().filter(|player| !order.contains(player)).collect();
+    order.extend(unranked);
+
+    let byes = |player: PlayerId| schedule.rounds.iter().filter(|round| round.out.contains(&player)).count();
+
+    // The sort is stable, so the lowest-ranked come first among players with as many byes.
+    let mut out: Vec<PlayerId> = order.iter().rev().copied().collect();
+    out.sort_by_key(|&player| byes(player));
+    out.truncate(params.outCount());
+    order.retain(|player| !out.contains(player));
+
+    let report = analysis::analyze(schedule, params.playerCount);
+    let weights = CostWeights::default();
+
+    let repeats = |teams: [[PlayerId; 2]; 2]| {
+        let [[a, b], [c, d]] = teams;
+        weights.repeatPartner * (report.partnerCount(a, b) + report.partnerCount(c, d)) as f64
+            + weights.repeatOpponent * [(a, c), (a, d), (b, c), (b, d)].iter().map(|&(x, y)| report.opponentCount(x, y)).sum::<usize>() as f64
+    };
+
+    let mut tables = Vec::new();
+
+    while order.len() >= 4 {
+
+        let window = order.len().min(SWISS_WINDOW);
+        let mut best: Option<(f64, [usize; 3], [[PlayerId; 2]; 2])> = None;
+
+        for i in 1..window {
+            for j in i + 1..window {
+                for k in j + 1..window {
+
+                    let [leader, x, y, z] = [order[0], order[i], order[j], order[k]];
+                    let climb = (i + j + k - 6) as f64;
+
+                    for teams in [[[leader, x], [y, z]], [[leader, y], [x, z]], [[leader, z], [x, y]]] {
+
+                        let cost = repeats(teams) + climb;
+
+                        if best.as_ref().map_or(true, |(bestCost, _, _)| cost < *bestCost) {
+                            best = Some((cost, [i, j, k], teams));
+                        }
+                    }
+                }
+            }
+        }
+
+        let Some((_, [i, j, k], [first, second])) = best else { break };
+
+        for index in [k, j, i, 0] {
+            order.remove(index);
+        }
+
+        tables.push(Table { teams: vec![Team { players: first.to_vec() }, Team { players: second.to_vec() }], dealer: 0 });
+    }
+
+    out.extend(order);
+
+    let mut next = schedule.clone();
+    next.rounds.push(Round { tables, out });
+    balanceSeats(&mut next, params.playerCount, roundIndex);
+
+    Ok(next)
+}
+
+/// How many of the best players left each Swiss table is picked from.
+const SWISS_WINDOW: usize = 8;
+
+/// Puts the played rounds back in front of newly built `rounds` and seats the new ones.
+fn finish(params: &ScheduleParams, rounds: Vec<Round>) -> Schedule {
+
+    let played = params.playedRounds();
+    let mut schedule = Schedule { seed: params.seed, rounds: played.iter().cloned().chain(rounds).collect() };
+
+    // Table numbers in rounds built from results rank the tables, so they are never shuffled.
+    if params.keepTables && !params.mode.roundByRound() {
+        orderTables(&mut schedule, played.len(), &params.activePins());
+    }
+
+    balanceSeats(&mut schedule, params.playerCount, played.len());
+    schedule
+}
+
+/// Moves players around `round` until it keeps every pin on it, returning the players who now
+/// have to stay where they are.
+fn applyPins(round: &mut Round, roundIndex: usize, pins: &[Pin], params: &ScheduleParams) -> Result<Vec<PlayerId>, ScheduleError> {
+
+    let pins: Vec<&Pin> = pins.iter().filter(|pin| pin.round() == roundIndex).collect();
+    let sizes = params.tableSizes();
+    let active = params.activePlayers();
+
+    let misfit = ScheduleError::PinDoesNotFit { round: roundIndex };
+    let conflict = ScheduleError::ConflictingPins { round: roundIndex };
+
+    if pins.iter().any(|pin| pin.players().iter().any(|player| !active.contains(player))) {
+        return Err(misfit);
+    }
+
+    if let Some(Pin::Round { content, .. }) = pins.iter().find(|pin| matches!(pin, Pin::Round { .. })) {
+
+        if pins.len() > 1 {
+            return Err(conflict);
+        }
+
+        let shaped = content.tables.len() == sizes.len() && content.tables.iter().zip(&sizes).all(|(table, &size)| table.fits(size, params.tableFormat()));
+
+        if !shaped || sorted(&pins[0].players()) != active {
+            return Err(misfit);
+        }
+
+        *round = content.clone();
+        return Ok(active);
+    }
+
+    let mut locked: Vec<PlayerId> = Vec::new();
+    let mut pinnedTables: Vec<usize> = Vec::new();
+
+    // Whole tables go first, since a single seat can make room anywhere else.
+    for pin in &pins {
+        if let Pin::Table { table, content, .. } = pin {
+
+            if !sizes.get(*table).map_or(false, |&size| content.fits(size, params.tableFormat())) {
+                return Err(misfit);
+            }
+
+            if pinnedTables.contains(table) {
+                return Err(conflict);
+            }
+
+            pinnedTables.push(*table);
+
+            for (teamIndex, team) in content.teams.iter().enumerate() {
+                for (slot, &player) in team.players.iter().enumerate() {
+
+                    if locked.contains(&player) {
+                        return Err(conflict);
+                    }
+
+                    let occupant = round.tables[*table].teams[teamIndex].players[slot];
+                    round.swapPlayers(player, occupant);
+                    locked.push(player);
+                }
+            }
+        }
+    }
+
+    for pin in &pins {
+        if let Pin::Seat { table, player, .. } = pin {
+
+            if *table >= round.tables.len() {
+                return Err(misfit);
+            }
+
+            if round.teamOf(*player).map(|(seated, _)| seated) == Some(*table) {
+                if !locked.contains(player) {
+                    locked.push(*player);
+                }
+                continue;
+            }
+
+            if locked.contains(player) {
+                return Err(conflict);
+            }
+
+            let occupant = round.tables[*table].players().into_iter().find(|seated| !locked.contains(seated)).ok_or(conflict.clone())?;
+            round.swapPlayers(*player, occupant);
+            locked.push(*player);
+        }
+    }
+
+    Ok(locked)
+}
+
+/// How many times players in `rounds` partner or oppose someone they already sat with in
+/// `played`.
+fn repeatMeetings(played: &[Round], rounds: &[Round]) -> usize {
+
+    let meetings = |round: &Round| -> Vec<(PlayerId, PlayerId)> {
+
+        let mut pairs = Vec::new();
+
+        for table in &round.tables {
+            let players = table.players();
+
+            for (index, &player) in players.iter().enumerate() {
+                pairs.extend(players[index + 1..].iter().map(|&other| (player.min(other), player.max(other))));
+            }
+        }
+
+        pairs
+    };
+
+    let met: HashSet<(PlayerId, PlayerId)> = played.iter().flat_map(meetings).collect();
+    rounds.iter().flat_map(meetings).filter(|pair| met.contains(pair)).count()
+}
+
+/// Renumbers the tables in each round so as many players as possible keep the table they had
+/// the round before. Only tables of the same size trade numbers, and who sits with whom is
+/// left alone. The first `frozen` rounds and any round with a pin keep their numbers.
+pub fn orderTables(schedule: &mut Schedule, frozen: usize, pins: &[Pin]) {
+
+    for roundIndex in frozen.max(1)..schedule.rounds.len() {
+
+        if pins.iter().any(|pin| pin.round() == roundIndex) {
+            continue;
+        }
+
+        let (earlier, later) = schedule.rounds.split_at_mut(roundIndex);
+        let previous = &earlier[roundIndex - 1];
+        let round = &mut later[0];
+
+        let tables = std::mem::take(&mut round.tables);
+        let sizes: Vec<usize> = tables.iter().map(|table| table.players().len()).collect();
+
+        // Every same-sized (table, number) pairing, best overlap with last round first.
+        let mut pairings: Vec<(usize, usize, usize)> = Vec::new();
+
+        for (index, table) in tables.iter().enumerate() {
+            for slot in (0..tables.len()).filter(|&slot| sizes[slot] == sizes[index]) {
+                let overlap = previous.tables.get(slot)
+                    .map_or(0, |before| table.players().iter().filter(|player| before.players().contains(player)).count());
+                pairings.push((overlap, index, slot));
+            }
+        }
+
+        pairings.sort_by_key(|&(overlap, index, slot)| (Reverse(overlap), index, slot));
+
+        let mut order: Vec<Option<usize>> = vec![None; tables.len()];
+        let mut placed = vec![false; tables.len()];
+
+        for (_, index, slot) in pairings {
+            if order[slot].is_none() && !placed[index] {
+                order[slot] = Some(index);
+                placed[index] = true;
+            }
+        }
+
+        let mut tables: Vec<Option<Table>> = tables.into_iter().map(Some).collect();
+        round.tables = order.into_iter().filter_map(|index| tables[index?].take()).collect();
+    }
+}
+
+/// Picks who takes each team's first seat and who deals first at every table, handing both to
+/// whoever has had them least so far. Only the order within teams changes, so partners and
+/// opponents stay as they were. The first `frozen` rounds are left alone but still count.
+pub fn balanceSeats(schedule: &mut Schedule, playerCount: usize, frozen: usize) {
+
+    let mut firstSeats = vec![0; playerCount + 1];
+    let mut deals = vec![0; playerCount + 1];
+
+    for round in &schedule.rounds[..frozen.min(schedule.rounds.len())] {
+        for table in &round.tables {
+
+            for team in &table.teams {
+                if let Some(&first) = team.players.first() {
+                    firstSeats[first] += 1;
+                }
+            }
+
+            if let Some(dealer) = table.dealerPlayer() {
+                deals[dealer] += 1;
+            }
+        }
+    }
+
+    for round in schedule.rounds.iter_mut().skip(frozen) {
+        for table in &mut round.tables {
+
+            for team in &mut table.teams {
+                if let Some(first) = (0..team.players.len()).min_by_key(|&slot| (firstSeats[team.players[slot]], slot)) {
+                    team.players.swap(0, first);
+                    firstSeats[team.players[0]] += 1;
+                }
+            }
+
+            let seating = table.seating();
+            table.dealer = (0..seating.len()).min_by_key(|&seat| (deals[seating[seat]], seat)).unwrap_or(0);
+
+            if let Some(&dealer) = seating.get(table.dealer) {
+                deals[dealer] += 1;
+            }
+        }
+    }
+}
+
+/// How many swaps `keepConstraints` tries in a round before giving up.
+const CONSTRAINT_ATTEMPTS: usize = 2_000;
+
+/// Catches constraints no card could keep before any rounds are built.
+fn checkConstraints(params: &ScheduleParams) -> Result<(), ScheduleError> {
+
+    for (index, &constraint) in params.constraints.iter().enumerate() {
+
+        if constraint.a == constraint.b || [constraint.a, constraint.b].iter().any(|&player| player == 0 || player > params.playerCount) {
+            return Err(ScheduleError::InvalidConstraint(constraint));
+        }
+
+        for &other in &params.constraints[index + 1..] {
+
+            let samePair = (other.a, other.b) == (constraint.a, constraint.b) || (other.a, other.b) == (constraint.b, constraint.a);
+            let kinds = [constraint.kind, other.kind];
+            let always = kinds.contains(&ConstraintKind::AlwaysPartners);
+
+            // Two players can't always and never partner, and a team of two only has room for
+            // one always-partner each.
+            let clash = match samePair {
+                true => always && kinds.contains(&ConstraintKind::NeverPartners),
+                false => kinds == [ConstraintKind::AlwaysPartners; 2]
+                    && params.tableFormat().teamSize() == 2
+                    && [other.a, other.b].iter().any(|player| [constraint.a, constraint.b].contains(player)),
+            };
+
+            if clash {
+                return Err(ScheduleError::ConflictingConstraints(constraint, other));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn brokenConstraint(rounds: &[Round], constraints: &[Constraint]) -> Option<(usize, Constraint)> {
+
+    rounds.iter().enumerate().find_map(|(roundIndex, round)| {
+        constraints.iter().find(|constraint| !constraint.holds(round)).map(|&constraint| (roundIndex, constraint))
+    })
+}
+
+/// Swaps players within a round until every constraint holds, never keeping a swap that breaks
+/// more than it fixes. Seats at the tables are tried first so sit-out counts stay even, and
+/// only then are sit-outs traded too. Players in `locked` never move. Returns a constraint that
+/// still fails when it gives up.
+fn keepConstraints(round: &mut Round, constraints: &[Constraint], locked: &[PlayerId], rng: &mut StdRng) -> Result<(), Constraint> {
+
+    let broken = |round: &Round| constraints.iter().filter(|constraint| !constraint.holds(round)).count();
+    let mut count = broken(round);
+
+    for attempt in 0..CONSTRAINT_ATTEMPTS {
+
+        if count == 0 {
+            return Ok(());
+        }
+
+        let failing: Vec<&Constraint> = constraints.iter().filter(|constraint| !constraint.holds(round)).collect();
+        let constraint = failing[rng.gen_range(0..failing.len())];
+        let moving = if rng.gen() { constraint.a } else { constraint.b };
+
+        let candidates = match attempt < CONSTRAINT_ATTEMPTS / 2 {
+            true => round.players(),
+            false => round.players().into_iter().chain(round.out.iter().copied()).collect(),
+        };
+
+        let other = candidates[rng.gen_range(0..candidates.len())];
+
+        if other == moving || locked.contains(&moving) || locked.contains(&other) || (attempt < CONSTRAINT_ATTEMPTS / 2 && round.out.contains(&moving)) {
+            continue;
+        }
+
+        round.swapPlayers(moving, other);
+        let newCount = broken(round);
+
+        if newCount <= count {
+            count = newCount;
+        } else {
+            round.swapPlayers(moving, other);
+        }
+    }
+
+    match constraints.iter().find(|constraint| !constraint.holds(round)) {
+        Some(&constraint) => Err(constraint),
+        None => Ok(()),
+    }
+}
+
+/// Round-robin between pairs by the circle method: one slot stays put while the rest turn, so
+/// no two pairs meet twice until every pair has met. With an odd pair count the empty slot is
+/// the bye. Cards longer than a full round-robin start the circle over.
+fn roundRobin(pairCount: usize, gameCount: usize, rng: &mut StdRng) -> Vec<Round> {
+
+    let mut slots: Vec<Option<usize>> = (1..=pairCount).map(Some).collect();
+    slots.shuffle(rng);
+
+    if pairCount % 2 == 1 {
+        slots.push(None);
+    }
+
+    let players = |pair: usize| vec![2 * pair - 1, 2 * pair];
+    let mut full: Vec<Round> = Vec::with_capacity(gameCount);
+
+    for _ in 0..gameCount {
+
+        let mut round = Round::default();
+
+        for index in 0..slots.len() / 2 {
+            match (slots[index], slots[slots.len() - 1 - index]) {
+                (Some(a), Some(b)) => round.tables.push(Table {
+                    teams: vec![Team { players: players(a) }, Team { players: players(b) }],
+                    dealer: 0,
+                }),
+                (Some(pair), None) | (None, Some(pair)) => round.out = players(pair),
+                (None, None) => {}
+            }
+        }
+
+        full.push(round);
+        slots[1..].rotate_right(1);
+    }
+
+    full
+}
+
+fn shuffle(playerCount: usize, tableCount: usize, shortTables: &[usize], gameCount: usize, outCount: usize, seed: u64) -> Vec<Round> {
+
+    // Every random draw comes from this generator so the same seed always gives the same card.
+    let mut seededRng = StdRng::seed_from_u64(seed);
+
+    let mut full: Vec<Round> = Vec::with_capacity(gameCount);
+
+    let mut game: Vec<Table> = Vec::with_capacity(tableCount);
+    let mut out: Vec<PlayerId> = Vec::with_capacity(outCount);
+
+    // Seats at three- and five-handed tables are handed out the same way as sit-outs, so nobody
+    // gets stuck at the odd table more than their share.
+    let outs = sitOuts(playerCount, gameCount, outCount + shortTables.iter().sum::<usize>(), &mut seededRng);
+
+    let mut players: Vec<PlayerId>;
+    let mut usedPartners: Vec<(PlayerId, PlayerId)> = Vec::new();
+    let mut usedNeighbors: Vec<(PlayerId, PlayerId)> = Vec::new();
+
+    for roundOut in &outs {
+
+        game.clear();
+        out.clone_from(roundOut);
+        players = (1..=playerCount).filter(|player| !out.contains(player)).collect();
+
+        for _ in 0..tableCount {
+
+            let mut pAA = seededRng.gen_range(0..players.len());
+            let mut pAB = seededRng.gen_range(0..players.len());
+
+            let mut teamAChecks = 50;
+
+            while pAA == pAB || (teamAChecks >= 0 && usedPartners.contains(&(players[pAA], players[pAB]))) {
+
+                pAA = seededRng.gen_range(0..players.len());
+                pAB = seededRng.gen_range(0..players.len());
+                teamAChecks -= 1;
+            }
+
+            let teamA = (players[pAA], players[pAB]);
+
+            usedPartners.push((teamA.0, teamA.1));
+            usedPartners.push((teamA.1, teamA.0));
+
+            if pAB > pAA {
+                pAB -= 1;
+            }
+            players.remove(pAA);
+            players.remove(pAB);
+
+            let mut pBA = seededRng.gen_range(0..players.len());
+            let mut pBB = seededRng.gen_range(0..players.len());
+
+            let mut teamBChecks = 50;
+            let mut neighborCheckThreshold = 20;
+
+            while pBA == pBB || (neighborCheckThreshold >= 0 && (usedNeighbors.contains(&(teamA.0, players[pBA]))
+                                                                || usedNeighbors.contains(&(players[pBA], teamA.1))
+                                                                || usedNeighbors.contains(&(teamA.1, players[pBB]))
+                                                                || usedNeighbors.contains(&(players[pBB], teamA.0)))) {
+
+                while pBA == pBB || (teamBChecks >= 0 && usedPartners.contains(&(players[pBA], players[pBB]))) {
+
+                    pBA = seededRng.gen_range(0..players.len());
+                    pBB = seededRng.gen_range(0..players.len());
+                    teamBChecks -= 1;
+                }
+
+                neighborCheckThreshold -= 1;
+            }
+
+            let teamB = (players[pBA], players[pBB]);
+
+            usedNeighbors.push((teamA.0, teamB.0));
+            usedNeighbors.push((teamB.0, teamA.0));
+            usedNeighbors.push((teamB.0, teamA.1));
+            usedNeighbors.push((teamA.1, teamB.0));
+            usedNeighbors.push((teamA.1, teamB.1));
+            usedNeighbors.push((teamB.1, teamA.1));
+            usedNeighbors.push((teamB.1, teamA.0));
+            usedNeighbors.push((teamA.0, teamB.1));
+
+            usedPartners.push((teamB.0, teamB.1));
+            usedPartners.push((teamB.1, teamB.0));
+
+            if pBB > pBA {
+                pBB -= 1;
+            }
+            players.remove(pBA);
+            players.remove(pBB);
+
+            game.push(Table {
+                teams: vec![
+                    Team { players: vec![teamA.0, teamA.1] },
+                    Team { players: vec![teamB.0, teamB.1] },
+                ],
+                dealer: 0,
+            });
+        }
+
+        if !shortTables.is_empty() {
+
+            out.shuffle(&mut seededRng);
+
+            for &size in shortTables {
+                game.push(Table {
+                    teams: out.drain(..size).map(|player| Team { players: vec![player] }).collect(),
+                    dealer: 0,
+                });
+            }
+        }
+
+        full.push(Round { tables: game.clone(), out: out.clone() });
+    }
+
+    full
+}
+
+/// How many random deals `shuffleTeams` scores for each round.
+const DEAL_ATTEMPTS: usize = 200;
+
+/// Random search for tables of larger teams. Each round is the best of a batch of random deals,
+/// scored by how often its partners and opponents have already met.
+fn shuffleTeams(playerCount: usize, teamSize: usize, tableCount: usize, gameCount: usize, outCount: usize, seed: u64) -> Vec<Round> {
+
+    let mut seededRng = StdRng::seed_from_u64(seed);
+
+    let outs = sitOuts(playerCount, gameCount, outCount, &mut seededRng);
+
+    let mut partners = vec![vec![0; playerCount + 1]; playerCount + 1];
+    let mut opponents = vec![vec![0; playerCount + 1]; playerCount + 1];
+    let mut full: Vec<Round> = Vec::with_capacity(gameCount);
+
+    for out in outs {
+
+        let players: Vec<PlayerId> = (1..=playerCount).filter(|player| !out.contains(player)).collect();
+        let mut best: Option<(usize, Vec<PlayerId>)> = None;
+
+        for _ in 0..DEAL_ATTEMPTS {
+
+            let mut deal = players.clone();
+            deal.shuffle(&mut seededRng);
+
+            // Repeat partners weigh more than repeat opponents, as they do in the optimizer.
+            let mut score = 0;
+
+            for table in deal.chunks(2 * teamSize).take(tableCount) {
+                let (teamA, teamB) = table.split_at(teamSize);
+
+                for team in [teamA, teamB] {
+                    for (index, &player) in team.iter().enumerate() {
+                        score += team[index + 1..].iter().map(|&partner| 5 * partners[player][partner]).sum::<usize>();
+                    }
+                }
+
+                for &player in teamA {
+                    score += teamB.iter().map(|&opponent| opponents[player][opponent]).sum::<usize>();
+                }
+            }
+
+            let better = match &best {
+                Some((bestScore, _)) => score < *bestScore,
+                None => true,
+            };
+
+            if better {
+                best = Some((score, deal));
+            }
+        }
+
+        let deal = best.map(|(_, deal)| deal).unwrap_or_default();
+        let mut tables: Vec<Table> = Vec::with_capacity(tableCount);
+
+        for table in deal.chunks(2 * teamSize).take(tableCount) {
+            let (teamA, teamB) = table.split_at(teamSize);
+
+            for (team, other) in [(teamA, teamB), (teamB, teamA)] {
+                for &player in team {
+                    for &partner in team {
+                        if partner != player {
+                            partners[player][partner] += 1;
+                        }
+                    }
+
+                    for &opponent in other {
+                        opponents[player][opponent] += 1;
+                    }
+                }
+            }
+
+            tables.push(Table {
+                teams: vec![Team { players: teamA.to_vec() }, Team { players: teamB.to_vec() }],
+                dealer: 0,
+            });
+        }
+
+        full.push(Round { tables, out });
+    }
+
+    full
+}
+
+/// How many shuffled orderings `sitOuts` searches at each gap before keeping the last gap met.
+const SIT_OUT_RESTARTS: usize = 4;
+
+/// Rounds one search may try before it gives up on its ordering.
+const SIT_OUT_STEPS: usize = 5_000;
+
+/// How many seeded greedy passes `sitOuts` makes when no search finds a clean card.
+const SIT_OUT_ATTEMPTS: usize = 64;
+
+/// Chooses who sits out each round. Sit-out counts never differ by more than one and no two
+/// players sit out together a second time. Within that, the gap between any player's
+/// sit-outs is as long as a search can find, and each round prefers whoever has gone longest
+/// without sitting out. Cards too long for every group to be new fall back to greedy passes
+/// that repeat as few pairs as they can, which `Schedule::repeatedSitOut` reports.
+pub fn sitOuts(playerCount: usize, gameCount: usize, outCount: usize, rng: &mut StdRng) -> Vec<Vec<PlayerId>> {
+
+    let pairsPerRound = outCount * outCount.saturating_sub(1) / 2;
+
+    if outCount > 0 && gameCount * pairsPerRound <= playerCount * playerCount.saturating_sub(1) / 2 {
+
+        let mut search = |gap: usize| (0..SIT_OUT_RESTARTS).find_map(|_| SitOutSearch::new(playerCount, gameCount, outCount, gap, rng).run());
+        let mut found = None;
+
+        // Widens the gap until a search comes up empty, since clean cards are quick to find
+        // and each gap that can't be met costs a whole search. Nobody sits out back to back
+        // while there are enough players to go round twice.
+        let shortest = if playerCount >= 2 * outCount { 2 } else { 1 };
+
+        for gap in shortest..=playerCount / outCount {
+            match search(gap) {
+                Some(outs) => found = Some(outs),
+                None => break,
+            }
+        }
+
+        // Big groups out of few players can't both rest a round and stay new, and a repeated
+        // pair is the worse of the two.
+        if found.is_none() && shortest > 1 {
+            found = search(1);
+        }
+
+        if let Some(outs) = found {
+            return outs;
+        }
+    }
+
+    let mut best: Option<(usize, usize, Vec<Vec<PlayerId>>)> = None;
+
+    // Greedy picks can paint themselves into a corner at the end of a pass, so try a few
+    // tie-break orders and keep the one with the fewest repeated pairs, then the widest gap.
+    for _ in 0..SIT_OUT_ATTEMPTS {
+
+        let (repeats, gap, outs) = sitOutAttempt(playerCount, gameCount, outCount, rng);
+
+        let better = match &best {
+            Some((bestRepeats, bestGap, _)) => (repeats, Reverse(gap)) < (*bestRepeats, Reverse(*bestGap)),
+            None => true,
+        };
+
+        if better {
+            best = Some((repeats, gap, outs));
+        }
+
+        if repeats == 0 && gap >= playerCount / outCount.max(1) {
+            break;
+        }
+    }
+
+    best.map(|(_, _, outs)| outs).unwrap_or_default()
+}
+
+/// A backtracking search for sit-outs in which no pair sits out together twice and nobody
+/// sits out again within `gap` rounds, taking the same turns as `sitOutAttempt` wherever it can.
+struct SitOutSearch {
+    gameCount: usize,
+    outCount: usize,
+    gap: usize,
+    /// Tie-break order between players who are otherwise equal.
+    order: Vec<PlayerId>,
+    counts: Vec<usize>,
+    lastOut: Vec<Option<usize>>,
+    satTogether: Vec<Vec<bool>>,
+    rounds: Vec<Vec<PlayerId>>,
+    /// Where each round's players had last sat out, so a round can be taken back.
+    history: Vec<Vec<Option<usize>>>,
+    steps: usize,
+}
+
+impl SitOutSearch {
+    fn new(playerCount: usize, gameCount: usize, outCount: usize, gap: usize, rng: &mut StdRng) -> Self {
+
+        let mut order: Vec<PlayerId> = (1..=playerCount).collect();
+        order.shuffle(rng);
+
+        Self {
+            gameCount,
+            outCount,
+            gap,
+            order,
+            counts: vec![0; playerCount + 1],
+            lastOut: vec![None; playerCount + 1],
+            satTogether: vec![vec![false; playerCount + 1]; playerCount + 1],
+            rounds: Vec::with_capacity(gameCount),
+            history: Vec::with_capacity(gameCount),
+            steps: 0,
+        }
+    }
+
+    fn run(mut self) -> Option<Vec<Vec<PlayerId>>> {
+        self.fill().then_some(self.rounds)
+    }
+
+    /// Fills the rest of the rounds, returning false if they can't be filled.
+    fn fill(&mut self) -> bool {
+
+        if self.rounds.len() == self.gameCount {
+            return true;
+        }
+
+        if self.steps >= SIT_OUT_STEPS {
+            return false;
+        }
+
+        self.steps += 1;
+
+        let mut candidates = self.order.clone();
+        candidates.sort_by_key(|&player| (self.counts[player], self.lastOut[player].map_or(0, |round| round + 1)));
+
+        // Anyone below the next player's count has to go now or the counts drift apart by two.
+        let fewest = candidates.get(self.outCount).map_or(usize::MAX, |&player| self.counts[player]);
+        let pool: Vec<PlayerId> = candidates.iter().copied().filter(|&player| self.counts[player] == fewest).collect();
+        let mut out = Vec::with_capacity(self.outCount);
+
+        for player in candidates.into_iter().filter(|&player| self.counts[player] < fewest) {
+
+            if !self.allowed(player, &out) {
+                return false;
+            }
+
+            out.push(player);
+        }
+
+        self.choose(&mut out, &pool, 0)
+    }
+
+    /// Tries each way of making up `out` from `pool[from..]`, longest waiting first.
+    fn choose(&mut self, out: &mut Vec<PlayerId>, pool: &[PlayerId], from: usize) -> bool {
+
+        if out.len() == self.outCount {
+
+            self.sitOut(out);
+
+            if self.fill() {
+                return true;
+            }
+
+            self.undo();
+            return false;
+        }
+
+        for index in from..pool.len() {
+
+            if pool.len() - index < self.outCount - out.len() || self.steps >= SIT_OUT_STEPS {
+                break;
+            }
+
+            if self.allowed(pool[index], out) {
+
+                out.push(pool[index]);
+
+                if self.choose(out, pool, index + 1) {
+                    return true;
+                }
+
+                out.pop();
+            }
+        }
+
+        false
+    }
+
+    /// Whether `player` can sit out with `out` in the next round.
+    fn allowed(&self, player: PlayerId, out: &[PlayerId]) -> bool {
+        let rested = self.lastOut[player].map_or(true, |round| self.rounds.len() - round >= self.gap);
+        rested && !out.iter().any(|&other| self.satTogether[player][other])
+    }
+
+    fn sitOut(&mut self, out: &[PlayerId]) {
+
+        let mut out = out.to_vec();
+        out.sort_unstable();
+
+        for (index, &player) in out.iter().enumerate() {
+
+            self.counts[player] += 1;
+
+            for &other in &out[index + 1..] {
+                self.satTogether[player][other] = true;
+                self.satTogether[other][player] = true;
+            }
+        }
+
+        self.history.push(out.iter().map(|&player| self.lastOut[player]).collect());
+
+        for &player in &out {
+            self.lastOut[player] = Some(self.rounds.len());
+        }
+
+        self.rounds.push(out);
+    }
+
+    fn undo(&mut self) {
+
+        let (Some(out), Some(previous)) = (self.rounds.pop(), self.history.pop()) else { return };
+
+        for (index, &player) in out.iter().enumerate() {
+
+            self.counts[player] -= 1;
+            self.lastOut[player] = previous[index];
+
+            for &other in &out[index + 1..] {
+                self.satTogether[player][other] = false;
+                self.satTogether[other][player] = false;
+            }
+        }
+    }
+}
+
+/// One greedy pass of `sitOuts`, returning how many pairs sat out together more than once and
+/// the shortest gap between any player's sit-outs alongside the rounds themselves.
+fn sitOutAttempt(playerCount: usize, gameCount: usize, outCount: usize, rng: &mut StdRng) -> (usize, usize, Vec<Vec<PlayerId>>) {
+
+    let mut full: Vec<Vec<PlayerId>> = Vec::with_capacity(gameCount);
+
+    let mut counts = vec![0; playerCount + 1];
+    let mut lastOut: Vec<Option<usize>> = vec![None; playerCount + 1];
+    let mut satTogether = vec![vec![false; playerCount + 1]; playerCount + 1];
+    let mut repeats = 0;
+    let mut gap = usize::MAX;
+
+    // Shuffled up front so equal candidates aren't always taken in player order.
+    let mut order: Vec<PlayerId> = (1..=playerCount).collect();
+    order.shuffle(rng);
+
+    for gameIndex in 0..gameCount {
+
+        let mut candidates = order.clone();
+        candidates.sort_by_key(|&player| (counts[player], lastOut[player].map_or(0, |round| round + 1)));
+
+        // Anyone below the next player's count has to go now or the counts drift apart by two.
+        let fewest = candidates.get(outCount).map_or(usize::MAX, |&player| counts[player]);
+        let mut out: Vec<PlayerId> = candidates.iter().copied().filter(|&player| counts[player] < fewest).collect();
+        let pool: Vec<PlayerId> = candidates.iter().copied().filter(|&player| counts[player] == fewest).collect();
+
+        let need = outCount - out.len();
+        let clash = |group: &[PlayerId]| group.iter().enumerate().any(|(index, &player)| group[index + 1..].iter().any(|&other| satTogether[player][other]));
+
+        // Near the end of a pass the leftovers are forced into the next round, so pick a group
+        // that leaves them clean too, preferring whoever has waited longest.
+        if pool.len() > need && pool.len() <= need + outCount && pool.len() <= 16 {
+
+            let mut masks: Vec<u32> = (0..1u32 << pool.len()).filter(|mask| mask.count_ones() as usize == need).collect();
+            masks.sort_by_key(|mask| (0..pool.len()).filter(|bit| mask & (1 << bit) != 0).sum::<usize>());
+
+            let pick = masks.into_iter().find(|mask| {
+                let chosen: Vec<PlayerId> = out.iter().copied().chain((0..pool.len()).filter(|bit| mask & (1 << bit) != 0).map(|bit| pool[bit])).collect();
+                let rest: Vec<PlayerId> = (0..pool.len()).filter(|bit| mask & (1 << bit) == 0).map(|bit| pool[bit]).collect();
+                !clash(&chosen) && !clash(&rest)
+            });
+
+            if let Some(mask) = pick {
+                out.extend((0..pool.len()).filter(|bit| mask & (1 << bit) != 0).map(|bit| pool[bit]));
+            }
+        }
+
+        let mut skipped: Vec<PlayerId> = Vec::new();
+
+        for &player in &pool {
+
+            if out.len() == outCount {
+                break;
+            }
+
+            if out.iter().any(|&other| satTogether[player][other]) {
+                skipped.push(player);
+            } else {
+                out.push(player);
+            }
+        }
+
+        for player in skipped {
+            if out.len() < outCount {
+                out.push(player);
+            }
+        }
+
+        for (index, &player) in out.iter().enumerate() {
+
+            counts[player] += 1;
+
+            if let Some(previous) = lastOut[player] {
+                gap = gap.min(gameIndex - previous);
+            }
+
+            lastOut[player] = Some(gameIndex);
+
+            for &other in &out[index + 1..] {
+
+                if satTogether[player][other] {
+                    repeats += 1;
+                }
+
+                satTogether[player][other] = true;
+                satTogether[other][player] = true;
+            }
+        }
+
+        out.sort_unstable();
+        full.push(out);
+    }
+
+    (repeats, gap, full)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(playerCount: usize, gameCount: usize) -> ScheduleParams {
+        ScheduleParams { playerCount, gameCount, seed: playerCount as u64, ..Default::default() }
+    }
+
+    #[test]
+    fn generatedCardsValidate() {
+
+        for playerCount in 4..=14 {
+            for format in TableFormat::ALL {
+                for leftover in Leftover::ALL {
+
+                    let params = ScheduleParams { format, leftover, ..params(playerCount, 4) };
+
+                    match generate(&params) {
+                        Ok(schedule) => {
+                            assert_eq!(schedule.rounds.len(), params.gameCount);
+                            assert_eq!(schedule.validate(&params), Ok(()), "{} players, {:?}, {:?}", playerCount, format, leftover);
+                        }
+                        Err(error) => assert!(playerCount < format.seats(), "{} players, {:?}, {:?}: {}", playerCount, format, leftover, error),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generatedCardsValidateInEveryMode() {
+
+        for mode in [ScheduleMode::FixedPairs, ScheduleMode::Progressive, ScheduleMode::Swiss] {
+            for playerCount in (4..=20).step_by(2) {
+
+                let params = ScheduleParams { mode, ..params(playerCount, 6) };
+                let schedule = generate(&params).unwrap();
+
+                assert_eq!(schedule.rounds.len(), if mode == ScheduleMode::FixedPairs { params.gameCount } else { 1 });
+                assert_eq!(schedule.validate(&params), Ok(()), "{} players, {:?}", playerCount, mode);
+            }
+        }
+
+        assert_eq!(generate(&ScheduleParams { mode: ScheduleMode::FixedPairs, ..params(9, 6) }), Err(ScheduleError::UnpairedPlayer(9)));
+    }
+
+    #[test]
+    fn generatedCardsKeepConstraints() {
+
+        let constraints = vec![
+            Constraint { kind: ConstraintKind::AlwaysPartners, a: 1, b: 2 },
+            Constraint { kind: ConstraintKind::NeverPartners, a: 3, b: 4 },
+            Constraint { kind: ConstraintKind::NeverOpponents, a: 5, b: 6 },
+        ];
+
+        let params = ScheduleParams { constraints, ..params(13, 8) };
+        let schedule = generate(&params).unwrap();
+
+        assert_eq!(schedule.validate(&params), Ok(()));
+        assert!(schedule.rounds.iter().all(|round| params.constraints.iter().all(|constraint| constraint.holds(round))));
+    }
+
+    #[test]
+    fn regeneratingKeepsPlayedRounds() {
+
+        let first = generate(&params(12, 6)).unwrap();
+        let params = ScheduleParams { played: first.rounds[..3].to_vec(), absent: vec![5], ..params(12, 6) };
+        let schedule = generate(&params).unwrap();
+
+        assert_eq!(schedule.validate(&params), Ok(()));
+        assert_eq!(schedule.rounds[..3], first.rounds[..3]);
+        assert!(schedule.rounds[3..].iter().all(|round| !round.players().contains(&5) && !round.out.contains(&5)));
+    }
+
+    #[test]
+    fn validateFindsBrokenCards() {
+
+        let params = params(9, 4);
+        let schedule = generate(&params).unwrap();
+
+        let mut short = schedule.clone();
+        short.rounds.pop();
+        assert_eq!(short.validate(&params), Err(ScheduleError::WrongRoundCount { expected: 4, found: 3 }));
+
+        let mut repeated = schedule.clone();
+        let player = repeated.rounds[1].out[0];
+        repeated.rounds[1].tables[0].teams[0].players[0] = player;
+        assert_eq!(repeated.validate(&params), Err(ScheduleError::PlayerRepeated { round: 1, player }));
+
+        let mut unknown = schedule.clone();
+        unknown.rounds[2].out = vec![10];
+        assert_eq!(unknown.validate(&params), Err(ScheduleError::UnknownPlayer { round: 2, player: 10 }));
+
+        let mut missing = schedule;
+        let player = missing.rounds[3].out.remove(0);
+        assert_eq!(missing.validate(&params), Err(ScheduleError::PlayerMissing { round: 3, player }));
+    }
+
+    /// Each team at `table` as a sorted pair.
+    fn pairs(table: &Table) -> Vec<Vec<PlayerId>> {
+        table.teams.iter().map(|team| sorted(&team.players)).collect()
+    }
+
+    #[test]
+    fn progressMovesWinnersUpAndLosersDown() {
+
+        let params = ScheduleParams { mode: ScheduleMode::Progressive, ..params(12, 4) };
+        let first = generate(&params).unwrap();
+        let winners = [0, 1, 0];
+        let next = progress(&first, &params, &winners).unwrap();
+
+        assert_eq!(next.rounds.len(), 2);
+        assert_eq!(next.rounds[..1], first.rounds[..]);
+        assert_eq!(next.validate(&params), Ok(()));
+
+        let previous = &first.rounds[0];
+        let round = &next.rounds[1];
+        let team = |table: usize, won: bool| sorted(&previous.tables[table].teams[if won { winners[table] } else { 1 - winners[table] }].players);
+        let players = |table: &Table| sorted(&table.players());
+
+        // Head winners stay and meet the winners from below, whose losers stay for the winners
+        // from the foot, and the head losers drop to the foot.
+        assert_eq!(players(&round.tables[0]), sorted(&[team(0, true), team(1, true)].concat()));
+        assert_eq!(players(&round.tables[1]), sorted(&[team(1, false), team(2, true)].concat()));
+        assert_eq!(players(&round.tables[2]), sorted(&[team(2, false), team(0, false)].concat()));
+
+        // Pairs that stay together at a table split up to partner the pair arriving.
+        for table in &round.tables {
+            for pair in pairs(table) {
+                assert!(previous.tables.iter().all(|before| !pairs(before).contains(&pair)), "{:?} partner again", pair);
+            }
+        }
+    }
+
+    #[test]
+    fn progressBringsSitOutsInAtTheFoot() {
+
+        let params = ScheduleParams { mode: ScheduleMode::Progressive, ..params(13, 4) };
+        let first = generate(&params).unwrap();
+        let next = progress(&first, &params, &[1, 0, 1]).unwrap();
+
+        let previous = &first.rounds[0];
+        let round = &next.rounds[1];
+        let satOut = previous.out[0];
+
+        assert_eq!(next.validate(&params), Ok(()));
+        assert!(round.tables[2].players().contains(&satOut));
+        assert_eq!(round.out.len(), 1);
+        assert!(previous.tables[2].teams[0].players.contains(&round.out[0]));
+    }
+
+    #[test]
+    fn progressNeedsAWinnerForEveryTable() {
+
+        let params = ScheduleParams { mode: ScheduleMode::Progressive, ..params(12, 2) };
+        let first = generate(&params).unwrap();
+
+        assert_eq!(progress(&first, &params, &[0, 1]), Err(ScheduleError::WrongTableCount { round: 0, expected: 3, found: 2 }));
+        assert_eq!(progress(&first, &params, &[0, 2, 1]), Err(ScheduleError::MalformedTable { round: 0, table: 1 }));
+
+        let full = progress(&first, &params, &[0, 0, 0]).unwrap();
+        assert_eq!(progress(&full, &params, &[0, 0, 0]), Err(ScheduleError::WrongRoundCount { expected: 2, found: 3 }));
+    }
+
+    #[test]
+    fn swissGivesByesToTheLowestRanked() {
+
+        let params = ScheduleParams { mode: ScheduleMode::Swiss, ..params(14, 14) };
+        let ranking: Vec<PlayerId> = (1..=14).collect();
+        let mut schedule = generate(&params).unwrap();
+
+        while schedule.rounds.len() < params.gameCount {
+
+            let had: Vec<PlayerId> = schedule.rounds.iter().flat_map(|round| round.out.clone()).collect();
+            schedule = swiss(&schedule, &params, &ranking).unwrap();
+
+            let round = schedule.rounds.last().unwrap();
+            let expected: Vec<PlayerId> = ranking.iter().rev().copied().filter(|player| !had.contains(player)).take(2).collect();
+
+            // Everyone has one bye before anyone has a second.
+            if expected.len() == 2 {
+                assert_eq!(sorted(&round.out), sorted(&expected));
+            }
+
+            let leader = ranking.iter().find(|player| !round.out.contains(player)).unwrap();
+            assert!(round.tables[0].players().contains(leader));
+            assert_eq!(schedule.validate(&params), Ok(()));
+        }
+
+        let byes: Vec<usize> = ranking.iter().map(|player| schedule.rounds.iter().filter(|round| round.out.contains(player)).count()).collect();
+        assert_eq!(byes, vec![2; 14]);
+    }
+
+    #[test]
+    fn swissSeatsLateArrivalsLast() {
+
+        let params = ScheduleParams { mode: ScheduleMode::Swiss, absent: vec![9], ..params(9, 4) };
+        let first = generate(&params).unwrap();
+        let params = ScheduleParams { absent: Vec::new(), played: first.rounds.clone(), ..params };
+        let next = swiss(&first, &params, &[3, 1, 2, 4, 5, 6, 7, 8]).unwrap();
+
+        assert_eq!(next.rounds[1].out, vec![9]);
+        assert_eq!(next.validate(&params), Ok(()));
+    }
+
+    /// Checks cards of half and as many games as players for every player count that sits
+    /// people out.
+    #[test]
+    fn sitOutsNeverRepeatAPair() {
+
+        for playerCount in 8..=50 {
+
+            let outCount = playerCount % 4;
+
+            if outCount == 0 {
+                continue;
+            }
+
+            for gameCount in [playerCount / 2, playerCount] {
+
+                let outs = sitOuts(playerCount, gameCount, outCount, &mut StdRng::seed_from_u64(playerCount as u64));
+                let mut counts = vec![0; playerCount + 1];
+                let mut together = HashSet::new();
+
+                assert_eq!(outs.len(), gameCount);
+
+                for (round, out) in outs.iter().enumerate() {
+
+                    assert_eq!(out.len(), outCount);
+
+                    for (index, &player) in out.iter().enumerate() {
+                        counts[player] += 1;
+                        for &other in &out[index + 1..] {
+                            assert!(together.insert((player.min(other), player.max(other))), "{} players, {} games: {} and {} sit out together twice", playerCount, gameCount, player, other);
+                        }
+                        if round > 0 && 2 * outCount <= playerCount {
+                            assert!(!outs[round - 1].contains(&player), "{} players, {} games: {} sits out back to back", playerCount, gameCount, player);
+                        }
+                    }
+                }
+
+                let (fewest, most) = (counts[1..].iter().min().unwrap(), counts[1..].iter().max().unwrap());
+                assert!(most - fewest <= 1, "{} players, {} games: sit-outs range from {} to {}", playerCount, gameCount, fewest, most);
+            }
+        }
+    }
+
+    #[test]
+    fn tooLongCardsWarnAboutRepeatedSitOuts() {
+
+        // Eleven players sit three out, and their 55 pairs run out after eighteen rounds.
+        let short = params(11, 11);
+        assert_eq!(generate(&short).unwrap().repeatedSitOut(&short), None);
+
+        let long = params(11, 22);
+        assert!(matches!(generate(&long).unwrap().repeatedSitOut(&long), Some(ScheduleError::SitOutRepeated { .. })));
+
+        // Fixed pairs always sit out together.
+        let pairs = ScheduleParams { mode: ScheduleMode::FixedPairs, ..params(10, 5) };
+        assert_eq!(generate(&pairs).unwrap().repeatedSitOut(&pairs), None);
+    }
+}
@@ -0,0 +1,497 @@
+//! Results entered for the tables on a card.
+#![allow(non_snake_case)]
+use crate::schedule::{PlayerId, Round, Schedule, Table};
+use std::fmt;
+
+/// Points each team at a table finished with, as typed into the card. Kept as text so a
+/// half-typed entry stays on screen until it is fixed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct TableScore {
+    pub points: Vec<String>,
+    /// Every hand of the game, for tables scored hand by hand. `points` then holds their totals.
+    #[serde(default)]
+    pub hands: Vec<Hand>,
+}
+
+impl TableScore {
+    /// Parsed points for every team, or `None` while nothing has been entered.
+    pub fn parse(&self) -> Result<Option<Vec<u32>>, ScoreError> {
+
+        if self.points.iter().all(|points| points.trim().is_empty()) {
+            return Ok(None);
+        }
+
+        self.points.iter()
+            .map(|points| match points.trim() {
+                "" => Err(ScoreError::Missing),
+                text => match text.parse::<i64>() {
+                    Ok(points) if points < 0 => Err(ScoreError::Negative(text.to_owned())),
+                    Ok(points) => u32::try_from(points).map_err(|_| ScoreError::NotANumber(text.to_owned())),
+                    Err(_) => Err(ScoreError::NotANumber(text.to_owned())),
+                },
+            })
+            .collect::<Result<Vec<u32>, ScoreError>>()
+            .map(Some)
+    }
+
+    /// Anything about the entry worth flagging: text that isn't a score, more than one team at
+    /// `target`, or, once the round is `done`, nothing entered at all. Ties aren't flagged, since a
+    /// game stopped early can end level.
+    pub fn problem(&self, target: u32, done: bool) -> Option<ScoreError> {
+        match self.parse() {
+            Err(error) => Some(error),
+            Ok(None) => done.then_some(ScoreError::Missing),
+            Ok(Some(points)) if points.iter().filter(|&&team| team >= target).count() > 1 => Some(ScoreError::BothOnTarget(target)),
+            Ok(Some(_)) => None,
+        }
+    }
+
+    /// Points each team at `table` has from `hands`, and whether one of them has reached
+    /// `target`. Hands after the game was won don't count.
+    pub fn handTotals(&self, table: &Table, target: u32) -> (Vec<u32>, bool) {
+
+        let mut totals = vec![0; table.teams.len()];
+
+        for hand in &self.hands {
+
+            if totals.iter().any(|&points| points >= target) {
+                return (totals, true);
+            }
+
+            if let Some(team) = hand.scoringTeam(table) {
+                totals[team] += hand.result.points();
+            }
+        }
+
+        let finished = totals.iter().any(|&points| points >= target);
+        (totals, finished)
+    }
+
+    /// Index of the team with the most points.
+    pub fn winner(&self) -> Result<usize, ScoreError> {
+
+        let points = self.parse()?.ok_or(ScoreError::Missing)?;
+        let best = points.iter().copied().max().unwrap_or(0);
+
+        match points.iter().filter(|&&team| team == best).count() {
+            1 => Ok(points.iter().position(|&team| team == best).unwrap_or(0)),
+            _ => Err(ScoreError::Tied),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScoreError {
+    Missing,
+    NotANumber(String),
+    Negative(String),
+    BothOnTarget(u32),
+    Tied,
+}
+
+impl fmt::Display for ScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => write!(f, "no score yet"),
+            Self::NotANumber(text) => write!(f, "\"{}\" is not a score", text),
+            Self::Negative(text) => write!(f, "\"{}\" is below zero", text),
+            Self::BothOnTarget(target) => write!(f, "both teams have {} or more", target),
+            Self::Tied => write!(f, "the score is tied"),
+        }
+    }
+}
+
+impl std::error::Error for ScoreError {}
+
+/// Points that win a game of euchre unless the night is set up otherwise.
+pub const DEFAULT_TARGET: u32 = 10;
+
+/// How a hand played out for the team that named trump.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum HandResult {
+    /// Three or four tricks.
+    Made,
+    /// All five tricks.
+    March,
+    /// Three or four tricks playing alone.
+    LoneMade,
+    /// All five tricks playing alone.
+    Loner,
+    /// Two tricks or fewer, so the defenders score.
+    Euchred,
+}
+
+impl HandResult {
+    pub const ALL: [HandResult; 5] = [HandResult::Made, HandResult::March, HandResult::LoneMade, HandResult::Loner, HandResult::Euchred];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HandResult::Made => "Made",
+            HandResult::March => "March",
+            HandResult::LoneMade => "Alone, 3-4 tricks",
+            HandResult::Loner => "Loner march",
+            HandResult::Euchred => "Euchred",
+        }
+    }
+
+    /// Points the hand is worth, to the makers or, when euchred, to the defenders.
+    pub fn points(&self) -> u32 {
+        match self {
+            HandResult::Made | HandResult::LoneMade => 1,
+            HandResult::March | HandResult::Euchred => 2,
+            HandResult::Loner => 4,
+        }
+    }
+}
+
+/// One hand on a table's score sheet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Hand {
+    /// The player who named trump.
+    pub caller: PlayerId,
+    pub result: HandResult,
+}
+
+impl Hand {
+    /// The team at `table` the hand's points go to, or `None` if the caller isn't seated there.
+    pub fn scoringTeam(&self, table: &Table) -> Option<usize> {
+
+        let makers = table.teams.iter().position(|team| team.players.contains(&self.caller))?;
+
+        match self.result {
+            HandResult::Euchred => (0..table.teams.len()).find(|&team| team != makers),
+            _ => Some(makers),
+        }
+    }
+}
+
+/// One player's record from the hands on every score sheet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HandStats {
+    /// Hands the player named trump.
+    pub calls: usize,
+    pub marches: usize,
+    /// Loner marches.
+    pub loners: usize,
+    /// Hands the player helped euchre as a defender.
+    pub euchres: usize,
+    /// Calls the player was euchred on.
+    pub euchred: usize,
+}
+
+/// Hand records for players 1 to `playerCount`, indexed from 0. Only hands that counted toward
+/// a game of `target` points are included.
+pub fn handStats(schedule: &Schedule, scores: &Scores, playerCount: usize, target: u32) -> Vec<HandStats> {
+
+    let mut stats = vec![HandStats::default(); playerCount];
+
+    for (roundIndex, round) in schedule.rounds.iter().enumerate() {
+        for (tableIndex, table) in round.tables.iter().enumerate() {
+
+            let Some(score) = scores.table(roundIndex, tableIndex) else { continue };
+            let mut totals = vec![0; table.teams.len()];
+
+            for hand in &score.hands {
+
+                if totals.iter().any(|&points| points >= target) {
+                    break;
+                }
+
+                let Some(team) = hand.scoringTeam(table) else { continue };
+                totals[team] += hand.result.points();
+
+                if !(1..=playerCount).contains(&hand.caller) {
+                    continue;
+                }
+
+                let caller = hand.caller - 1;
+                stats[caller].calls += 1;
+
+                match hand.result {
+                    HandResult::March => stats[caller].marches += 1,
+                    HandResult::Loner => stats[caller].loners += 1,
+                    HandResult::Euchred => {
+                        stats[caller].euchred += 1;
+
+                        for &defender in table.teams[team].players.iter().filter(|&&player| player <= playerCount) {
+                            stats[defender - 1].euchres += 1;
+                        }
+                    }
+                    HandResult::Made | HandResult::LoneMade => {}
+                }
+            }
+        }
+    }
+
+    stats
+}
+
+/// Scores for every table of every round, indexed the same way as `Schedule::rounds`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Scores {
+    pub rounds: Vec<Vec<TableScore>>,
+}
+
+impl Scores {
+    pub fn table(&self, round: usize, table: usize) -> Option<&TableScore> {
+        self.rounds.get(round)?.get(table)
+    }
+
+    /// The score for a table with `teamCount` teams, making room for it if it's the first entry.
+    pub fn tableMut(&mut self, round: usize, table: usize, teamCount: usize) -> &mut TableScore {
+
+        if self.rounds.len() <= round {
+            self.rounds.resize(round + 1, Vec::new());
+        }
+
+        let tables = &mut self.rounds[round];
+
+        if tables.len() <= table {
+            tables.resize(table + 1, TableScore::default());
+        }
+
+        let score = &mut tables[table];
+        score.points.resize(teamCount, String::new());
+        score
+    }
+
+    /// Keeps the scores of tables that seat the same teams in `after` as they did in `before`,
+    /// clearing the rest and dropping any round `after` no longer has.
+    pub fn carryOver(&mut self, before: &[Round], after: &[Round]) {
+
+        self.rounds.truncate(after.len());
+
+        for (roundIndex, tables) in self.rounds.iter_mut().enumerate() {
+            for (tableIndex, score) in tables.iter_mut().enumerate() {
+
+                let teams = |rounds: &[Round]| rounds.get(roundIndex).and_then(|round| round.tables.get(tableIndex)).map(|table| table.teams.clone());
+
+                if teams(before) != teams(after) {
+                    *score = TableScore::default();
+                }
+            }
+        }
+    }
+
+    /// Gives the caller of every hand on the score sheets to `label` of that player.
+    pub fn relabel(&mut self, label: impl Fn(PlayerId) -> PlayerId) {
+        for hand in self.rounds.iter_mut().flatten().flat_map(|score| score.hands.iter_mut()) {
+            hand.caller = label(hand.caller);
+        }
+    }
+
+    /// The winning team at each of `tableCount` tables in `round`, or the first table without a
+    /// clear winner and why.
+    pub fn winners(&self, round: usize, tableCount: usize) -> Result<Vec<usize>, (usize, ScoreError)> {
+
+        (0..tableCount)
+            .map(|table| match self.table(round, table) {
+                Some(score) => score.winner().map_err(|error| (table, error)),
+                None => Err((table, ScoreError::Missing)),
+            })
+            .collect()
+    }
+}
+
+/// One player's results over every scored table on a card.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Standing {
+    pub player: PlayerId,
+    pub played: usize,
+    /// Rounds the player sat out while results were coming in.
+    pub satOut: usize,
+    pub wins: usize,
+    pub points: u32,
+    pub against: u32,
+    /// Wins of everyone the player has faced, counted once per game against them.
+    pub opponentWins: usize,
+}
+
+impl Standing {
+    pub fn difference(&self) -> i64 {
+        self.points as i64 - self.against as i64
+    }
+
+    /// The player's total for `rank`, plus their per-game average for every round they sat out
+    /// when `rules` credit sit-outs.
+    pub fn value(&self, rank: RankBy, rules: &RankingRules) -> f64 {
+
+        let total = match rank {
+            RankBy::Wins => self.wins as f64,
+            RankBy::Points => self.points as f64,
+            RankBy::Difference => self.difference() as f64,
+            RankBy::OpponentWins => self.opponentWins as f64,
+        };
+
+        match rules.sitOutCredit && self.played > 0 {
+            true => total * (self.played + self.satOut) as f64 / self.played as f64,
+            false => total,
+        }
+    }
+}
+
+/// A column the leaderboard can be sorted on, higher being better for each.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum RankBy {
+    Wins,
+    Points,
+    Difference,
+    /// Strength of schedule: the wins of everyone the player faced.
+    OpponentWins,
+}
+
+impl RankBy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RankBy::Wins => "Games won",
+            RankBy::Points => "Points scored",
+            RankBy::Difference => "Point difference",
+            RankBy::OpponentWins => "Opponents' wins",
+        }
+    }
+}
+
+/// How the leaderboard orders players.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct RankingRules {
+    /// Columns compared in turn, each breaking ties left by the ones before it.
+    pub order: Vec<RankBy>,
+    /// Counts each sit-out as a game at the player's own average, so sitting out neither helps
+    /// nor hurts a player's totals against those who played every round.
+    pub sitOutCredit: bool,
+}
+
+impl Default for RankingRules {
+    fn default() -> Self {
+        Self {
+            order: vec![RankBy::Wins, RankBy::OpponentWins, RankBy::Difference, RankBy::Points],
+            sitOutCredit: true,
+        }
+    }
+}
+
+/// Standings for players 1 to `playerCount`, best first by `rules`, and by player number when
+/// every column ties. Tables without a clear result are left out, and so are sit-outs from
+/// rounds with no results yet.
+pub fn standings(schedule: &Schedule, scores: &Scores, playerCount: usize, rules: &RankingRules) -> Vec<Standing> {
+
+    let mut standings: Vec<Standing> = (1..=playerCount).map(|player| Standing { player, ..Default::default() }).collect();
+    let mut opponents: Vec<Vec<PlayerId>> = vec![Vec::new(); playerCount + 1];
+
+    for (roundIndex, round) in schedule.rounds.iter().enumerate() {
+
+        let mut scored = false;
+
+        for (tableIndex, table) in round.tables.iter().enumerate() {
+
+            let Some(score) = scores.table(roundIndex, tableIndex) else { continue };
+            let (Ok(Some(points)), Ok(winner)) = (score.parse(), score.winner()) else { continue };
+
+            if points.len() != table.teams.len() {
+                continue;
+            }
+
+            scored = true;
+            let total: u32 = points.iter().sum();
+
+            for (teamIndex, team) in table.teams.iter().enumerate() {
+                for &player in team.players.iter().filter(|&&player| player <= playerCount) {
+
+                    let standing = &mut standings[player - 1];
+                    standing.played += 1;
+                    standing.wins += (teamIndex == winner) as usize;
+                    standing.points += points[teamIndex];
+                    standing.against += total - points[teamIndex];
+
+                    opponents[player].extend(table.teams.iter().enumerate().filter(|&(other, _)| other != teamIndex).flat_map(|(_, other)| other.players.iter().copied()));
+                }
+            }
+        }
+
+        if scored {
+            for &player in round.out.iter().filter(|&&player| player <= playerCount) {
+                standings[player - 1].satOut += 1;
+            }
+        }
+    }
+
+    let wins: Vec<usize> = standings.iter().map(|standing| standing.wins).collect();
+
+    for standing in &mut standings {
+        standing.opponentWins = opponents[standing.player].iter().filter(|&&opponent| opponent <= playerCount).map(|&opponent| wins[opponent - 1]).sum();
+    }
+
+    standings.sort_by(|a, b| {
+        rules.order.iter()
+            .map(|&rank| b.value(rank, rules).total_cmp(&a.value(rank, rules)))
+            .find(|order| order.is_ne())
+            .unwrap_or_else(|| a.player.cmp(&b.player))
+    });
+
+    standings
+}
+
+/// Leaderboard places for `standings` as sorted by `standings`: players level on every column
+/// share a place, and the place after them is skipped.
+pub fn places(standings: &[Standing], rules: &RankingRules) -> Vec<usize> {
+
+    let level = |a: &Standing, b: &Standing| rules.order.iter().all(|&rank| a.value(rank, rules) == b.value(rank, rules));
+    let mut places: Vec<usize> = Vec::with_capacity(standings.len());
+
+    for (index, standing) in standings.iter().enumerate() {
+        match index > 0 && level(&standings[index - 1], standing) {
+            true => places.push(places[index - 1]),
+            false => places.push(index + 1),
+        }
+    }
+
+    places
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::Team;
+
+    fn score(points: &[&str]) -> TableScore {
+        TableScore { points: points.iter().map(|&points| points.to_owned()).collect(), ..Default::default() }
+    }
+
+    #[test]
+    fn problemFlagsBadEntries() {
+
+        assert_eq!(score(&["", ""]).problem(10, false), None);
+        assert_eq!(score(&["", " "]).problem(10, true), Some(ScoreError::Missing));
+        assert_eq!(score(&["10", ""]).problem(10, false), Some(ScoreError::Missing));
+        assert_eq!(score(&["ten", "4"]).problem(10, false), Some(ScoreError::NotANumber("ten".to_owned())));
+        assert_eq!(score(&["10", "-2"]).problem(10, false), Some(ScoreError::Negative("-2".to_owned())));
+        assert_eq!(score(&["5000000000", "2"]).problem(10, false), Some(ScoreError::NotANumber("5000000000".to_owned())));
+        assert_eq!(score(&["10", "11"]).problem(10, true), Some(ScoreError::BothOnTarget(10)));
+        assert_eq!(score(&["10", "11"]).problem(15, true), None);
+    }
+
+    #[test]
+    fn problemAllowsGamesStoppedEarly() {
+
+        assert_eq!(score(&[" 10 ", "7"]).problem(10, true), None);
+        assert_eq!(score(&["6", "6"]).problem(10, true), None);
+        assert_eq!(score(&["6", "6"]).winner(), Err(ScoreError::Tied));
+        assert_eq!(score(&["3", "10"]).winner(), Ok(1));
+    }
+
+    #[test]
+    fn carryOverKeepsTablesThatStayTheSame() {
+
+        let table = |a: PlayerId, b: PlayerId, c: PlayerId, d: PlayerId| Table { teams: vec![Team { players: vec![a, b] }, Team { players: vec![c, d] }], dealer: 0 };
+        let round = |tables: Vec<Table>| Round { tables, out: Vec::new() };
+
+        let before = vec![round(vec![table(1, 2, 3, 4), table(5, 6, 7, 8)]), round(vec![table(1, 3, 5, 7), table(2, 4, 6, 8)]), round(vec![table(1, 4, 6, 7), table(2, 3, 5, 8)])];
+        let after = vec![round(vec![table(1, 2, 3, 4), table(5, 6, 8, 7)]), round(vec![table(1, 3, 5, 7), table(2, 4, 6, 8)])];
+
+        let mut scores = Scores { rounds: vec![vec![score(&["10", "4"]), score(&["10", "6"])], vec![score(&["3", "10"])], vec![score(&["10", "9"])]] };
+        scores.carryOver(&before, &after);
+
+        assert_eq!(scores.rounds, vec![vec![score(&["10", "4"]), TableScore::default()], vec![score(&["3", "10"])]]);
+    }
+}
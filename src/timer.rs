@@ -0,0 +1,193 @@
+//! Countdown for timed rounds.
+#![allow(non_snake_case)]
+
+/// What players do with the hand in play when a round's time runs out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum OvertimeRule {
+    /// Play out the hand in progress, then score the game as it stands.
+    #[default]
+    FinishHand,
+    /// Stop at once and score the game as it stands.
+    ScoreAsIs,
+}
+
+impl OvertimeRule {
+    pub const ALL: [OvertimeRule; 2] = [OvertimeRule::FinishHand, OvertimeRule::ScoreAsIs];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OvertimeRule::FinishHand => "Finish the current hand",
+            OvertimeRule::ScoreAsIs => "Score as is",
+        }
+    }
+
+    /// What to tell the room when time is up.
+    pub fn instruction(&self) -> &'static str {
+        match self {
+            OvertimeRule::FinishHand => "Finish the hand in play, then enter the scores as they stand.",
+            OvertimeRule::ScoreAsIs => "Stop play and enter the scores as they stand.",
+        }
+    }
+}
+
+/// A round countdown that can be paused. Times are in seconds on the UI clock, which is passed
+/// in as `now` so the timer itself holds no clock.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct RoundTimer {
+    pub minutes: u32,
+    /// Minutes left when the countdown turns to a warning.
+    pub warningMinutes: u32,
+    pub overtime: OvertimeRule,
+    /// When the countdown reaches zero while it is running.
+    #[serde(skip)]
+    endsAt: Option<f64>,
+    /// Seconds left while paused, or `None` before the round has started.
+    #[serde(skip)]
+    paused: Option<f64>,
+}
+
+impl Default for RoundTimer {
+    fn default() -> Self {
+        Self {
+            minutes: 20,
+            warningMinutes: 2,
+            overtime: OvertimeRule::default(),
+            endsAt: None,
+            paused: None,
+        }
+    }
+}
+
+impl RoundTimer {
+    pub fn running(&self) -> bool {
+        self.endsAt.is_some()
+    }
+
+    pub fn start(&mut self, now: f64) {
+        if self.endsAt.is_none() {
+            self.endsAt = Some(now + self.remaining(now));
+        }
+    }
+
+    pub fn pause(&mut self, now: f64) {
+        self.paused = Some(self.remaining(now));
+        self.endsAt = None;
+    }
+
+    /// Back to the full length, stopped.
+    pub fn reset(&mut self) {
+        self.endsAt = None;
+        self.paused = None;
+    }
+
+    /// Seconds left, never below zero.
+    pub fn remaining(&self, now: f64) -> f64 {
+        match (self.endsAt, self.paused) {
+            (Some(endsAt), _) => (endsAt - now).max(0.0),
+            (None, Some(paused)) => paused,
+            (None, None) => self.minutes as f64 * 60.0,
+        }
+    }
+
+    /// True once the time left is within the warning time.
+    pub fn warning(&self, now: f64) -> bool {
+        self.remaining(now) <= self.warningMinutes as f64 * 60.0
+    }
+
+    /// Stops the countdown once it reaches zero, returning true on the call that stops it.
+    pub fn expire(&mut self, now: f64) -> bool {
+
+        if self.running() && self.remaining(now) <= 0.0 {
+            self.pause(now);
+            return true;
+        }
+
+        false
+    }
+
+    /// The time left as minutes and seconds, rounded up so zero shows only once time is up.
+    pub fn display(&self, now: f64) -> String {
+        let seconds = self.remaining(now).ceil() as u64;
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timer() -> RoundTimer {
+        RoundTimer { minutes: 10, warningMinutes: 2, ..Default::default() }
+    }
+
+    #[test]
+    fn startingCountsDownFromTheFullLength() {
+
+        let mut timer = timer();
+        assert_eq!(timer.remaining(100.0), 600.0);
+
+        timer.start(100.0);
+        assert!(timer.running());
+        assert_eq!(timer.remaining(160.0), 540.0);
+
+        // Starting again while running doesn't restart the countdown.
+        timer.start(200.0);
+        assert_eq!(timer.remaining(200.0), 500.0);
+    }
+
+    #[test]
+    fn pausingHoldsTheTimeLeft() {
+
+        let mut timer = timer();
+        timer.start(0.0);
+        timer.pause(90.0);
+
+        assert!(!timer.running());
+        assert_eq!(timer.remaining(1000.0), 510.0);
+
+        timer.start(1000.0);
+        assert_eq!(timer.remaining(1010.0), 500.0);
+
+        timer.reset();
+        assert_eq!(timer.remaining(2000.0), 600.0);
+    }
+
+    #[test]
+    fn expiringStopsOnceAtZero() {
+
+        let mut timer = timer();
+        assert!(!timer.expire(1000.0));
+
+        timer.start(0.0);
+        assert!(!timer.expire(599.0));
+        assert!(timer.expire(650.0));
+        assert!(!timer.running());
+        assert_eq!(timer.remaining(700.0), 0.0);
+        assert!(!timer.expire(700.0));
+    }
+
+    #[test]
+    fn warningStartsWithinTheWarningTime() {
+
+        let mut timer = timer();
+        timer.start(0.0);
+
+        assert!(!timer.warning(479.0));
+        assert!(timer.warning(480.0));
+        assert!(timer.warning(600.0));
+    }
+
+    #[test]
+    fn displayRoundsUpToWholeSeconds() {
+
+        let mut timer = timer();
+        assert_eq!(timer.display(0.0), "10:00");
+
+        timer.start(0.0);
+        assert_eq!(timer.display(0.5), "10:00");
+        assert_eq!(timer.display(61.0), "8:59");
+        assert_eq!(timer.display(599.5), "0:01");
+        assert_eq!(timer.display(600.0), "0:00");
+    }
+}
@@ -0,0 +1,164 @@
+//! Exact rotations built from whist tournament designs.
+//!
+//! A whist design seats 4k or 4k+1 players so that over one full cycle of rounds every pair of
+//! players partners exactly once and opposes each other exactly twice. With 4k+1 players each
+//! player also sits out exactly once per cycle. Each design below is stored as a single base
+//! round on the group Z_a × Z_b (plain Z_a when b is 1); adding each group element to the base
+//! round in turn gives the rest of the cycle.
+//!
+//! Player counts without a design here fall back to the random search in `schedule`.
+#![allow(non_snake_case)]
+use crate::schedule::{PlayerId, Round, Table, Team};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+struct WhistDesign {
+    playerCount: usize,
+    /// Elements are numbered `x * b + y` for x in Z_a and y in Z_b. With 4k players the extra
+    /// element `a * b` is a fixed point that stays put in every round.
+    group: (usize, usize),
+    /// Base round tables as `[a, b, c, d]`, meaning a and b partner against c and d.
+    tables: &'static [[usize; 4]],
+}
+
+const DESIGNS: &[WhistDesign] = &[
+    WhistDesign { playerCount: 4, group: (3, 1), tables: &[[0, 1, 2, 3]] },
+    WhistDesign { playerCount: 5, group: (5, 1), tables: &[[1, 4, 2, 3]] },
+    WhistDesign { playerCount: 8, group: (7, 1), tables: &[[0, 4, 2, 3], [1, 6, 5, 7]] },
+    WhistDesign { playerCount: 9, group: (3, 3), tables: &[[1, 2, 3, 6], [4, 8, 5, 7]] },
+    WhistDesign { playerCount: 12, group: (11, 1), tables: &[[0, 10, 3, 9], [1, 5, 8, 6], [2, 11, 4, 7]] },
+    WhistDesign { playerCount: 13, group: (13, 1), tables: &[[1, 11, 12, 5], [2, 10, 7, 3], [4, 6, 8, 9]] },
+    WhistDesign { playerCount: 16, group: (15, 1), tables: &[[0, 6, 15, 4], [1, 11, 5, 8], [2, 10, 9, 7], [3, 14, 13, 12]] },
+    WhistDesign { playerCount: 17, group: (17, 1), tables: &[[1, 7, 16, 4], [2, 11, 15, 12], [3, 10, 9, 5], [6, 8, 13, 14]] },
+    WhistDesign { playerCount: 20, group: (19, 1), tables: &[[0, 10, 13, 6], [1, 9, 12, 8], [2, 16, 14, 11], [3, 4, 5, 18], [7, 19, 15, 17]] },
+    WhistDesign { playerCount: 21, group: (21, 1), tables: &[[1, 11, 14, 7], [2, 17, 12, 9], [3, 20, 19, 18], [4, 16, 13, 5], [6, 8, 10, 15]] },
+    WhistDesign { playerCount: 24, group: (23, 1), tables: &[[0, 10, 7, 12], [1, 17, 16, 23], [2, 3, 4, 21], [5, 19, 13, 15], [6, 14, 20, 9], [8, 11, 22, 18]] },
+    WhistDesign { playerCount: 25, group: (25, 1), tables: &[[1, 21, 4, 8], [2, 14, 19, 5], [3, 11, 9, 18], [6, 7, 20, 22], [10, 16, 15, 12], [13, 23, 17, 24]] },
+    WhistDesign { playerCount: 28, group: (27, 1), tables: &[[0, 15, 8, 3], [1, 11, 17, 26], [2, 25, 7, 20], [4, 24, 21, 5], [6, 9, 22, 23], [10, 18, 16, 14], [12, 27, 19, 13]] },
+    WhistDesign { playerCount: 29, group: (29, 1), tables: &[[1, 21, 19, 12], [2, 27, 8, 14], [3, 18, 4, 25], [5, 24, 7, 10], [6, 17, 11, 9], [13, 26, 22, 23], [15, 20, 16, 28]] },
+    WhistDesign { playerCount: 32, group: (31, 1), tables: &[[0, 6, 17, 13], [1, 22, 5, 21], [2, 26, 30, 28], [3, 31, 15, 27], [4, 12, 20, 25], [7, 18, 8, 9], [10, 24, 19, 16], [11, 29, 14, 23]] },
+    WhistDesign { playerCount: 33, group: (33, 1), tables: &[[1, 26, 3, 12], [2, 14, 8, 13], [4, 22, 21, 17], [5, 11, 23, 30], [6, 29, 32, 19], [7, 10, 15, 31], [9, 28, 24, 25], [16, 27, 20, 18]] },
+    WhistDesign { playerCount: 36, group: (35, 1), tables: &[[0, 24, 20, 34], [1, 3, 9, 22], [2, 31, 4, 13], [5, 23, 12, 17], [6, 14, 32, 35], [7, 11, 10, 26], [8, 28, 21, 33], [15, 16, 19, 29], [18, 25, 27, 30]] },
+    WhistDesign { playerCount: 37, group: (37, 1), tables: &[[1, 36, 31, 6], [2, 35, 25, 12], [4, 33, 13, 24], [8, 29, 26, 11], [16, 21, 15, 22], [32, 5, 30, 7], [27, 10, 23, 14], [17, 20, 9, 28], [34, 3, 18, 19]] },
+    WhistDesign { playerCount: 40, group: (39, 1), tables: &[[39, 0, 13, 26], [1, 38, 10, 29], [2, 37, 8, 31], [3, 36, 17, 22], [4, 35, 19, 20], [5, 34, 7, 32], [6, 33, 11, 28], [9, 30, 12, 27], [14, 25, 18, 21], [15, 24, 16, 23]] },
+    WhistDesign { playerCount: 41, group: (41, 1), tables: &[[1, 40, 32, 9], [6, 35, 28, 13], [36, 5, 4, 37], [11, 30, 24, 17], [25, 16, 21, 20], [27, 14, 3, 38], [39, 2, 18, 23], [29, 12, 26, 15], [10, 31, 33, 8], [19, 22, 34, 7]] },
+    WhistDesign { playerCount: 44, group: (43, 1), tables: &[[0, 39, 31, 43], [1, 6, 9, 21], [2, 4, 3, 28], [5, 26, 10, 16], [7, 23, 11, 14], [8, 38, 13, 24], [12, 35, 29, 37], [15, 32, 22, 36], [17, 41, 20, 30], [18, 19, 33, 42], [25, 40, 27, 34]] },
+    WhistDesign { playerCount: 45, group: (45, 1), tables: &[[1, 40, 20, 36], [2, 44, 19, 34], [3, 16, 25, 43], [4, 23, 26, 37], [5, 41, 11, 33], [6, 8, 17, 24], [7, 12, 10, 31], [9, 21, 14, 22], [13, 30, 15, 29], [18, 38, 32, 42], [27, 28, 35, 39]] },
+    WhistDesign { playerCount: 48, group: (47, 1), tables: &[[0, 47, 12, 41], [1, 44, 22, 37], [2, 42, 43, 46], [3, 36, 26, 34], [4, 39, 21, 30], [5, 32, 18, 24], [6, 29, 11, 13], [7, 38, 8, 27], [9, 20, 28, 33], [10, 23, 19, 45], [14, 31, 16, 17], [15, 40, 25, 35]] },
+    WhistDesign { playerCount: 49, group: (7, 7), tables: &[[7, 42, 3, 4], [8, 48, 17, 39], [30, 26, 33, 23], [27, 29, 37, 19], [2, 5, 28, 21], [44, 12, 32, 24], [36, 20, 15, 41], [13, 43, 38, 18], [35, 14, 1, 6], [40, 16, 22, 34], [45, 11, 46, 10], [9, 47, 31, 25]] },
+];
+
+/// Rounds in one full cycle of the design for `playerCount`, if there is one.
+pub fn cycleLength(playerCount: usize) -> Option<usize> {
+    DESIGNS.iter()
+        .find(|design| design.playerCount == playerCount)
+        .map(|design| design.group.0 * design.group.1)
+}
+
+/// Builds `gameCount` rounds from the design for `playerCount`, or `None` when there is none.
+/// Player labels and round order are drawn from `rng`. Rounds past one full cycle start another
+/// cycle under fresh labels, since no card can avoid repeat partners beyond that point.
+pub fn rounds(playerCount: usize, gameCount: usize, rng: &mut StdRng) -> Option<Vec<Round>> {
+
+    let design = DESIGNS.iter().find(|design| design.playerCount == playerCount)?;
+
+    let (a, b) = design.group;
+    let order = a * b;
+
+    let mut full: Vec<Round> = Vec::with_capacity(gameCount);
+
+    while full.len() < gameCount {
+
+        let mut labels: Vec<PlayerId> = (1..=playerCount).collect();
+        labels.shuffle(rng);
+
+        let mut shifts: Vec<usize> = (0..order).collect();
+        shifts.shuffle(rng);
+
+        for shift in shifts.into_iter().take(gameCount - full.len()) {
+
+            let develop = |element: usize| {
+                if element == order {
+                    element
+                } else {
+                    ((element / b + shift / b) % a) * b + (element % b + shift % b) % b
+                }
+            };
+
+            let tables = design.tables.iter()
+                .map(|table| Table {
+                    teams: vec![
+                        Team { players: vec![labels[develop(table[0])], labels[develop(table[1])]] },
+                        Team { players: vec![labels[develop(table[2])], labels[develop(table[3])]] },
+                    ],
+                    dealer: 0,
+                })
+                .collect();
+
+            // Without a fixed point the base round leaves element 0 sitting out.
+            let out = if playerCount == order { vec![labels[develop(0)]] } else { Vec::new() };
+
+            full.push(Round { tables, out });
+        }
+    }
+
+    Some(full)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    /// Deals one full cycle of every design and checks that each pair partners once and
+    /// opposes twice, and that with 4k+1 players everyone sits out once.
+    #[test]
+    fn everyDesignIsAWhistTournament() {
+
+        for design in DESIGNS {
+
+            let playerCount = design.playerCount;
+            let cycle = cycleLength(playerCount).unwrap();
+            let full = rounds(playerCount, cycle, &mut StdRng::seed_from_u64(playerCount as u64)).unwrap();
+
+            let mut partners = vec![vec![0; playerCount + 1]; playerCount + 1];
+            let mut opponents = vec![vec![0; playerCount + 1]; playerCount + 1];
+            let mut outs = vec![0; playerCount + 1];
+
+            for round in &full {
+
+                assert_eq!(round.tables.len(), playerCount / 4, "{} players", playerCount);
+
+                for table in &round.tables {
+
+                    for team in &table.teams {
+                        let [a, b] = [team.players[0], team.players[1]];
+                        partners[a][b] += 1;
+                        partners[b][a] += 1;
+                    }
+
+                    for &a in &table.teams[0].players {
+                        for &b in &table.teams[1].players {
+                            opponents[a][b] += 1;
+                            opponents[b][a] += 1;
+                        }
+                    }
+                }
+
+                for &player in &round.out {
+                    outs[player] += 1;
+                }
+            }
+
+            for a in 1..=playerCount {
+
+                assert_eq!(outs[a], playerCount % 4, "{} players: player {} sits out {} times", playerCount, a, outs[a]);
+
+                for b in a + 1..=playerCount {
+                    assert_eq!(partners[a][b], 1, "{} players: {} and {} partner {} times", playerCount, a, b, partners[a][b]);
+                    assert_eq!(opponents[a][b], 2, "{} players: {} and {} oppose {} times", playerCount, a, b, opponents[a][b]);
+                }
+            }
+        }
+    }
+}